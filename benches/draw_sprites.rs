@@ -0,0 +1,82 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use corvus::core::{
+    assets::{AssetServer, Assets, Image, TextureAtlas},
+    ecs::{
+        components::{Sprite, Transform},
+        systems::render_system,
+        World,
+    },
+    render::{Rect, SpriteBatch},
+    resources::specifications::DynamicAtlas,
+};
+
+const TEXTURES: usize = 8;
+const SPRITES_PER_TEXTURE: usize = 1_250;
+const ITERATIONS: u32 = 100;
+
+fn report(name: &str, elapsed: Duration) {
+    let sprites = (TEXTURES * SPRITES_PER_TEXTURE) as f64 * ITERATIONS as f64;
+
+    println!(
+        "{name:<24} {:>10.2?} per frame, {:>8.1} ns per sprite",
+        elapsed / ITERATIONS,
+        elapsed.as_nanos() as f64 / sprites
+    );
+}
+
+fn main() {
+    let mut asset_server = AssetServer::new();
+    let mut images = Assets::<Image>::new();
+    let atlases = Assets::<TextureAtlas>::new();
+    let dynamic_atlas = DynamicAtlas::new();
+    let mut world = World::new();
+    world.register_component::<Transform>();
+    world.register_component::<Sprite>();
+
+    let handles: Vec<_> = (0..TEXTURES)
+        .map(|i| asset_server.add(&mut images, Image::missing(&format!("bench/sheet_{i}.png"))))
+        .collect();
+
+    for i in 0..TEXTURES * SPRITES_PER_TEXTURE {
+        let entity = world.spawn();
+        world.insert_component(
+            entity,
+            Transform::new(
+                glam::vec3((i % 320) as f32, (i / 320) as f32, 0.0),
+                glam::vec2(1.0, 1.0),
+                0.0,
+                glam::vec2(0.0, 0.0),
+            ),
+        );
+        world.insert_component(
+            entity,
+            Sprite::new(
+                handles[i % TEXTURES].clone(),
+                Rect::new(0, 0, 16, 16),
+                [1.0, 1.0, 1.0, 1.0],
+                false,
+                false,
+            ),
+        );
+    }
+
+    let mut sprite_batch = SpriteBatch::new();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        render_system::draw_sprites(
+            &world,
+            &asset_server,
+            &images,
+            &atlases,
+            &dynamic_atlas,
+            &mut sprite_batch,
+        );
+        black_box(sprite_batch.len());
+        sprite_batch.clear();
+    }
+    report("draw_sprites", start.elapsed());
+}
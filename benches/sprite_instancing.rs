@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use corvus::core::{
+    assets::ImageSampler,
+    render::{Rect, SpriteBatch, SpriteInstance},
+    utils::HandleId,
+};
+
+const SPRITES: usize = 100_000;
+const ITERATIONS: u32 = 20;
+
+struct SpriteInput {
+    position: glam::Vec2,
+    scale: glam::Vec2,
+    rotation: f32,
+    origin: glam::Vec2,
+    source_rect: Rect,
+    flip_horizontal: bool,
+}
+
+// The per-vertex layout `draw_sprites` emitted before sprites were instanced.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct LegacyVertex {
+    pos: [f32; 2],
+    color: [f32; 4],
+    uv: [f32; 2],
+}
+
+#[derive(Default)]
+struct LegacyDrawCall {
+    vertex_data: Vec<LegacyVertex>,
+    // Uploaded as wgpu::IndexFormat::Uint16, like the renderer this replaced.
+    index_data: Vec<u16>,
+}
+
+type LegacyBatch = HashMap<(HandleId, Option<ImageSampler>), LegacyDrawCall>;
+
+fn report(name: &str, elapsed: Duration) {
+    let sprites = SPRITES as f64 * ITERATIONS as f64;
+
+    println!(
+        "{name:<12} {:>10.2?} per frame, {:>6.1} ns per sprite",
+        elapsed / ITERATIONS,
+        elapsed.as_nanos() as f64 / sprites
+    );
+}
+
+fn legacy_draw(sprite: &SpriteInput, uv_rect: [f32; 4], batch: &mut LegacyBatch) {
+    let position = [[-1.0, 1.0], [-1.0, -1.0], [1.0, -1.0], [1.0, 1.0]]
+        .iter()
+        .map(|&[x, y]| {
+            let size = glam::vec2(sprite.source_rect.w as f32, sprite.source_rect.h as f32);
+            let scaled = glam::vec2(x, y) * size * sprite.scale;
+            let originated = scaled + sprite.origin * size;
+            let rotated = glam::Mat2::from_angle(sprite.rotation.to_radians()) * originated;
+            let translated = rotated + sprite.position;
+
+            [translated.x, translated.y]
+        })
+        .collect::<Vec<_>>();
+
+    let [u_min, v_min, u_max, v_max] = uv_rect;
+    let (u_min, u_max) = match sprite.flip_horizontal {
+        true => (u_max, u_min),
+        false => (u_min, u_max),
+    };
+    let uv_coords = vec![
+        [u_min, v_min],
+        [u_min, v_max],
+        [u_max, v_max],
+        [u_max, v_min],
+    ];
+
+    let batch = batch.entry((HandleId::new(0, 0), None)).or_default();
+    let first = batch.vertex_data.len() as u16;
+    batch.vertex_data.extend(
+        position
+            .iter()
+            .zip(uv_coords)
+            .map(|(&pos, uv)| LegacyVertex {
+                pos,
+                color: [1.0, 1.0, 1.0, 1.0],
+                uv,
+            }),
+    );
+    batch
+        .index_data
+        .extend([0, 1, 2, 2, 3, 0].map(|index| first.wrapping_add(index)));
+}
+
+fn instanced_draw(sprite: &SpriteInput, uv_rect: [f32; 4], batch: &mut SpriteBatch) {
+    let size = glam::vec2(sprite.source_rect.w as f32, sprite.source_rect.h as f32);
+    let rotation = glam::Mat2::from_angle(sprite.rotation.to_radians());
+
+    batch.draw(SpriteInstance {
+        texture_id: HandleId::new(0, 0),
+        transform: glam::Affine2::from_mat2_translation(
+            rotation * glam::Mat2::from_diagonal(size * sprite.scale),
+            rotation * (sprite.origin * size) + sprite.position,
+        ),
+        uv_rect,
+        color: [1.0, 1.0, 1.0, 1.0],
+        flip_horizontal: sprite.flip_horizontal,
+        flip_vertical: false,
+        sampler: None,
+    });
+}
+
+fn main() {
+    let sprites = (0..SPRITES)
+        .map(|i| SpriteInput {
+            position: glam::vec2((i % 320) as f32, (i / 320) as f32),
+            scale: glam::vec2(1.0, 1.0),
+            rotation: (i % 360) as f32,
+            origin: glam::vec2(0.0, 0.0),
+            source_rect: Rect::new(0, 0, 16, 16),
+            flip_horizontal: i % 2 == 0,
+        })
+        .collect::<Vec<_>>();
+    let uv_rect = [0.0, 0.0, 0.25, 0.25];
+
+    let mut legacy_batch = LegacyBatch::default();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for sprite in &sprites {
+            legacy_draw(sprite, uv_rect, &mut legacy_batch);
+        }
+        black_box(legacy_batch.len());
+        legacy_batch.clear();
+    }
+    report("per-vertex", start.elapsed());
+
+    let mut sprite_batch = SpriteBatch::new();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for sprite in &sprites {
+            instanced_draw(sprite, uv_rect, &mut sprite_batch);
+        }
+        black_box(sprite_batch.instance_count());
+        sprite_batch.clear();
+    }
+    report("instanced", start.elapsed());
+}
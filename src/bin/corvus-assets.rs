@@ -0,0 +1,265 @@
+use std::{path::Path, process::ExitCode};
+
+use corvus::core::assets::{
+    io::{self, Compression, FileAssetReader},
+    loaders::{ImageLoader, SpriteSheetLoader},
+    processing::{AssetProcessor, ProcessReport},
+    AssetServer, META_EXTENSION,
+};
+
+const DEFAULT_SOURCE: &str = "assets";
+const DEFAULT_OUTPUT: &str = "assets.corvus";
+const DEFAULT_PROCESSED_OUTPUT: &str = "processed_assets";
+
+fn print_usage() {
+    eprintln!("Usage: corvus-assets pack [--no-compression] [SOURCE_DIR] [OUTPUT]");
+    eprintln!("       corvus-assets meta [--reset] [SOURCE_DIR]");
+    eprintln!("       corvus-assets validate [--manifest PATH] [SOURCE_DIR]");
+    eprintln!("       corvus-assets process [--manifest PATH] [SOURCE_DIR] [OUTPUT_DIR]");
+}
+
+fn pack(args: &[String]) -> ExitCode {
+    let mut compression = Compression::Deflate;
+    let mut paths = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--no-compression" => compression = Compression::None,
+            flag if flag.starts_with("--") => {
+                eprintln!("Unknown option \"{flag}\"");
+                print_usage();
+                return ExitCode::FAILURE;
+            }
+            path => paths.push(path),
+        }
+    }
+
+    let source = paths.first().copied().unwrap_or(DEFAULT_SOURCE);
+    let output = paths.get(1).copied().unwrap_or(DEFAULT_OUTPUT);
+
+    let entries = match io::pack_directory(source, output, compression) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("Failed to pack \"{source}\" into \"{output}\": {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let size: u64 = entries.iter().map(|(_, entry)| entry.size).sum();
+    let stored_size: u64 = entries.iter().map(|(_, entry)| entry.stored_size).sum();
+
+    for (path, entry) in &entries {
+        println!("{path:<48} {:>10} -> {:>10}", entry.size, entry.stored_size);
+    }
+
+    println!(
+        "Packed {} files into \"{output}\" ({size} bytes -> {stored_size} bytes)",
+        entries.len()
+    );
+
+    ExitCode::SUCCESS
+}
+
+fn meta(args: &[String]) -> ExitCode {
+    let mut reset = false;
+    let mut paths = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--reset" => reset = true,
+            flag if flag.starts_with("--") => {
+                eprintln!("Unknown option \"{flag}\"");
+                print_usage();
+                return ExitCode::FAILURE;
+            }
+            path => paths.push(path),
+        }
+    }
+
+    let source = Path::new(paths.first().copied().unwrap_or(DEFAULT_SOURCE));
+
+    let files = match io::list_files(source) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("Failed to read \"{}\": {err}", source.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut asset_server = AssetServer::new();
+    asset_server.set_default_source(FileAssetReader::new(source));
+    asset_server.register_loader(ImageLoader);
+    asset_server.register_loader(SpriteSheetLoader);
+
+    let mut written = 0;
+    let mut failed = false;
+
+    for file in files {
+        if file
+            .extension()
+            .is_some_and(|extension| extension == META_EXTENSION)
+        {
+            continue;
+        }
+
+        let Ok(relative_path) = file.strip_prefix(source) else {
+            continue;
+        };
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+
+        let meta = match asset_server.regenerate_meta(&relative_path, reset) {
+            Ok(Some(meta)) => meta,
+            Ok(None) => continue,
+            Err(err) => {
+                eprintln!("Skipping \"{relative_path}\": {err}");
+                failed = true;
+                continue;
+            }
+        };
+
+        let meta_path = format!("{}.{META_EXTENSION}", file.display());
+        if let Err(err) = std::fs::write(&meta_path, meta + "\n") {
+            eprintln!("Failed to write \"{meta_path}\": {err}");
+            failed = true;
+            continue;
+        }
+
+        println!("{relative_path}.{META_EXTENSION}");
+        written += 1;
+    }
+
+    println!("Wrote {written} meta files in \"{}\"", source.display());
+
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+fn processor_args(args: &[String]) -> Option<(Option<&str>, Vec<&str>)> {
+    let mut manifest = None;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--manifest" => manifest = Some(args.next()?.as_str()),
+            flag if flag.starts_with("--") => {
+                eprintln!("Unknown option \"{flag}\"");
+                return None;
+            }
+            path => paths.push(path),
+        }
+    }
+
+    Some((manifest, paths))
+}
+
+fn create_processor(source: &str, manifest: Option<&str>) -> AssetProcessor {
+    let mut processor = AssetProcessor::new(source);
+    processor.register_loader(ImageLoader);
+    processor.register_loader(SpriteSheetLoader);
+
+    match manifest {
+        Some(manifest) => processor.with_manifest(manifest),
+        None => processor,
+    }
+}
+
+fn print_report(report: &ProcessReport) {
+    for path in &report.processed {
+        println!("processed {path}");
+    }
+
+    for path in &report.atlases {
+        println!("packed    {path}");
+    }
+
+    for path in &report.removed {
+        println!("removed   {path}");
+    }
+
+    for path in &report.unused {
+        println!("unused    {path}");
+    }
+
+    for err in &report.errors {
+        eprintln!("error     {err}");
+    }
+}
+
+fn validate(args: &[String]) -> ExitCode {
+    let Some((manifest, paths)) = processor_args(args) else {
+        print_usage();
+        return ExitCode::FAILURE;
+    };
+
+    let source = paths.first().copied().unwrap_or(DEFAULT_SOURCE);
+
+    let report = match create_processor(source, manifest).validate() {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("Failed to validate \"{source}\": {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    print_report(&report);
+    println!(
+        "Validated \"{source}\": {} errors, {} unused files",
+        report.errors.len(),
+        report.unused.len()
+    );
+
+    match report.is_ok() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn process(args: &[String]) -> ExitCode {
+    let Some((manifest, paths)) = processor_args(args) else {
+        print_usage();
+        return ExitCode::FAILURE;
+    };
+
+    let source = paths.first().copied().unwrap_or(DEFAULT_SOURCE);
+    let output = paths.get(1).copied().unwrap_or(DEFAULT_PROCESSED_OUTPUT);
+
+    let report = match create_processor(source, manifest).process(output) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("Failed to process \"{source}\" into \"{output}\": {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    print_report(&report);
+    println!(
+        "Processed \"{source}\" into \"{output}\": {} written, {} cached, {} removed, {} errors",
+        report.processed.len() + report.atlases.len(),
+        report.cached.len(),
+        report.removed.len(),
+        report.errors.len()
+    );
+
+    match report.is_ok() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match args.first().map(String::as_str) {
+        Some("pack") => pack(&args[1..]),
+        Some("meta") => meta(&args[1..]),
+        Some("validate") => validate(&args[1..]),
+        Some("process") => process(&args[1..]),
+        _ => {
+            print_usage();
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetError {
+    NotFound(String),
+    UnknownSource { path: String, source: String },
+    Io { path: String, message: String },
+    UnsupportedFormat(String),
+    MissingLoader { path: String, asset_type: String },
+    MissingLabel { path: String, label: String },
+    Decode { path: String, message: String },
+    Corrupted(String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "asset \"{path}\" was not found"),
+            Self::UnknownSource { path, source } => write!(
+                f,
+                "asset \"{path}\" refers to the unregistered source \"{source}\""
+            ),
+            Self::Io { path, message } => write!(f, "failed to read asset \"{path}\": {message}"),
+            Self::UnsupportedFormat(path) => {
+                write!(f, "asset \"{path}\" has an unsupported format")
+            }
+            Self::MissingLoader { path, asset_type } => write!(
+                f,
+                "no loader for \"{asset_type}\" is registered for asset \"{path}\""
+            ),
+            Self::MissingLabel { path, label } => {
+                write!(f, "asset \"{path}\" has no sub-asset labeled \"{label}\"")
+            }
+            Self::Decode { path, message } => {
+                write!(f, "failed to decode asset \"{path}\": {message}")
+            }
+            Self::Corrupted(path) => write!(f, "asset \"{path}\" failed its integrity check"),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
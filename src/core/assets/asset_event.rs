@@ -0,0 +1,10 @@
+use crate::core::utils::HandleId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetEvent {
+    Loaded(HandleId),
+    Modified(HandleId),
+    Failed(HandleId),
+    Unloaded(HandleId),
+    GroupFinished(String),
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use crate::core::utils::{HandleId, StrongHandle};
+
+pub(crate) struct GroupEntry {
+    pub handle: Arc<StrongHandle>,
+    pub size: Option<u64>,
+}
+
+impl GroupEntry {
+    pub fn id(&self) -> HandleId {
+        self.handle.id()
+    }
+}
+
+pub(crate) struct AssetGroup {
+    pub entries: Vec<GroupEntry>,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupProgress {
+    pub total: usize,
+    pub loaded: usize,
+    pub failed: usize,
+    pub loaded_bytes: u64,
+    pub failed_bytes: u64,
+    pub total_bytes: u64,
+}
+
+impl GroupProgress {
+    pub fn is_finished(&self) -> bool {
+        self.loaded + self.failed == self.total
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.total_bytes > 0 {
+            (self.loaded_bytes + self.failed_bytes) as f32 / self.total_bytes as f32
+        } else if self.total > 0 {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        } else {
+            1.0
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    any::{Any, TypeId},
+    sync::{mpsc, Mutex, MutexGuard},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::core::utils::{Handle, HandleId};
+
+use super::{
+    asset_meta::{meta_path, AssetMeta},
+    handle_allocator::HandleAllocator,
+    AssetError,
+};
+
+pub(crate) struct LoadDependency {
+    pub handle_id: HandleId,
+    pub path: String,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+pub(crate) struct LabeledAsset {
+    pub handle_id: HandleId,
+    pub label: String,
+    pub asset: Box<dyn Any + Send + Sync>,
+}
+
+pub struct LoadContext<'a> {
+    path: &'a str,
+    handle_allocator: &'a Mutex<HandleAllocator>,
+    drop_sender: &'a mpsc::Sender<HandleId>,
+    dependencies: Vec<LoadDependency>,
+    labeled_assets: Vec<LabeledAsset>,
+}
+
+impl<'a> LoadContext<'a> {
+    pub(crate) fn new(
+        path: &'a str,
+        handle_allocator: &'a Mutex<HandleAllocator>,
+        drop_sender: &'a mpsc::Sender<HandleId>,
+    ) -> Self {
+        Self {
+            path,
+            handle_allocator,
+            drop_sender,
+            dependencies: Vec::new(),
+            labeled_assets: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    pub fn handle<T>(&self) -> Handle<T> {
+        let handle_id = self.get_or_allocate(self.path);
+
+        self.strong_handle(handle_id)
+    }
+
+    pub fn resolve_path(&self, relative_path: &str) -> String {
+        match self.path.rfind('/') {
+            Some(index) => format!("{}/{relative_path}", &self.path[..index]),
+            None => relative_path.to_string(),
+        }
+    }
+
+    pub fn load<T: Send + Sync + 'static>(&mut self, path: &str) -> Handle<T> {
+        let handle_id = self.get_or_allocate(path);
+
+        self.dependencies.push(LoadDependency {
+            handle_id,
+            path: path.to_string(),
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+        });
+
+        self.strong_handle(handle_id)
+    }
+
+    pub fn add_labeled_asset<T: Send + Sync + 'static>(
+        &mut self,
+        label: &str,
+        asset: T,
+    ) -> Handle<T> {
+        let handle_id = self.get_or_allocate(&format!("{}#{label}", self.path));
+
+        self.labeled_assets.push(LabeledAsset {
+            handle_id,
+            label: label.to_string(),
+            asset: Box::new(asset),
+        });
+
+        self.strong_handle(handle_id)
+    }
+
+    pub(crate) fn into_parts(self) -> (Vec<LoadDependency>, Vec<LabeledAsset>) {
+        (self.dependencies, self.labeled_assets)
+    }
+
+    fn handle_allocator(&self) -> MutexGuard<'a, HandleAllocator> {
+        self.handle_allocator
+            .lock()
+            .expect("the handle allocator lock was poisoned")
+    }
+
+    fn get_or_allocate(&self, path: &str) -> HandleId {
+        let mut handle_allocator = self.handle_allocator();
+
+        handle_allocator
+            .get_id(path)
+            .unwrap_or_else(|| handle_allocator.allocate(Some(path)))
+    }
+
+    fn strong_handle<T>(&self, handle_id: HandleId) -> Handle<T> {
+        let strong_handle = self
+            .handle_allocator()
+            .strong_handle(handle_id, self.drop_sender)
+            .expect("a handle id returned by the allocator is always live");
+
+        Handle::strong(strong_handle)
+    }
+}
+
+pub trait AssetLoader: Send + Sync + 'static {
+    type Asset: Send + Sync + 'static;
+    type Settings: Default + Serialize + DeserializeOwned + Send + Sync + 'static;
+
+    fn extensions(&self) -> &[&str];
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        settings: &Self::Settings,
+        load_context: &mut LoadContext,
+    ) -> Result<Self::Asset, AssetError>;
+
+    // Decoded size in memory, used to budget per-frame uploads.
+    fn size_of(&self, _asset: &Self::Asset) -> usize {
+        std::mem::size_of::<Self::Asset>()
+    }
+}
+
+pub trait ErasedAssetLoader: Send + Sync + 'static {
+    fn extensions(&self) -> &[&str];
+    fn asset_type_id(&self) -> TypeId;
+    fn asset_type_name(&self) -> &'static str;
+    fn load(
+        &self,
+        bytes: &[u8],
+        meta: Option<&[u8]>,
+        load_context: &mut LoadContext,
+    ) -> Result<Box<dyn Any + Send + Sync>, AssetError>;
+    fn regenerate_meta(
+        &self,
+        path: &str,
+        meta: Option<&[u8]>,
+    ) -> Result<Option<String>, AssetError>;
+    fn size_of(&self, asset: &(dyn Any + Send + Sync)) -> usize;
+}
+
+impl<L: AssetLoader> ErasedAssetLoader for L {
+    fn extensions(&self) -> &[&str] {
+        AssetLoader::extensions(self)
+    }
+
+    fn asset_type_id(&self) -> TypeId {
+        TypeId::of::<L::Asset>()
+    }
+
+    fn asset_type_name(&self) -> &'static str {
+        std::any::type_name::<L::Asset>()
+    }
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        meta: Option<&[u8]>,
+        load_context: &mut LoadContext,
+    ) -> Result<Box<dyn Any + Send + Sync>, AssetError> {
+        let meta = parse_meta::<L::Settings>(load_context.path(), meta)?;
+        let asset = AssetLoader::load(self, bytes, &meta.settings, load_context)?;
+
+        Ok(Box::new(asset))
+    }
+
+    fn regenerate_meta(
+        &self,
+        path: &str,
+        meta: Option<&[u8]>,
+    ) -> Result<Option<String>, AssetError> {
+        let meta = parse_meta::<L::Settings>(path, meta)?;
+        let encode_error = |err: serde_json::Error| AssetError::Decode {
+            path: meta_path(path),
+            message: err.to_string(),
+        };
+
+        let settings = serde_json::to_value(&meta.settings).map_err(encode_error)?;
+        if settings.is_null() {
+            return Ok(None);
+        }
+
+        serde_json::to_string_pretty(&meta)
+            .map(Some)
+            .map_err(encode_error)
+    }
+
+    fn size_of(&self, asset: &(dyn Any + Send + Sync)) -> usize {
+        asset
+            .downcast_ref::<L::Asset>()
+            .map_or(0, |asset| AssetLoader::size_of(self, asset))
+    }
+}
+
+fn parse_meta<S: Default + DeserializeOwned>(
+    path: &str,
+    meta: Option<&[u8]>,
+) -> Result<AssetMeta<S>, AssetError> {
+    let Some(meta) = meta else {
+        return Ok(AssetMeta::default());
+    };
+
+    serde_json::from_slice(meta).map_err(|err| AssetError::Decode {
+        path: meta_path(path),
+        message: err.to_string(),
+    })
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+pub const META_EXTENSION: &str = "meta";
+
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct AssetMeta<S> {
+    #[serde(default)]
+    pub settings: S,
+}
+
+pub(crate) fn meta_path(path: &str) -> String {
+    format!("{path}.{META_EXTENSION}")
+}
@@ -1,40 +1,1367 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
 
-use crate::core::utils::{Handle, HandleId};
+use crate::core::utils::{Events, Handle, HandleId, StrongHandle};
+
+use super::{
+    asset_group::{AssetGroup, GroupEntry, GroupProgress},
+    asset_loader::{LabeledAsset, LoadDependency},
+    asset_meta::meta_path,
+    handle_allocator::HandleAllocator,
+    io::{ArchiveAssetReader, AssetReader, FileAssetReader, ARCHIVE_EXTENSION},
+    AssetError, AssetEvent, AssetLoader, AssetStats, Assets, ErasedAssetLoader, Image, LoadContext,
+    LoadState, RecursiveLoadState,
+};
+
+const MAX_WORKERS: usize = 4;
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+const MISSING_TEXTURE_PATH: &str = "corvus://missing";
+const ASSET_DIR_VARIABLE: &str = "CORVUS_ASSET_DIR";
+const ASSET_ARCHIVE_VARIABLE: &str = "CORVUS_ASSET_ARCHIVE";
+const SOURCE_SEPARATOR: &str = "://";
+const LABEL_SEPARATOR: char = '#';
+
+struct LoadJob {
+    handle_id: HandleId,
+    path: String,
+    source_path: String,
+    reader: Arc<dyn AssetReader>,
+    loader: Arc<dyn ErasedAssetLoader>,
+}
+
+struct AssetSources {
+    default: Arc<dyn AssetReader>,
+    named: HashMap<String, Arc<dyn AssetReader>>,
+}
+
+impl AssetSources {
+    fn resolve<'a>(&self, path: &'a str) -> Result<(Arc<dyn AssetReader>, &'a str), AssetError> {
+        let Some((source, source_path)) = path.split_once(SOURCE_SEPARATOR) else {
+            return Ok((self.default.clone(), path));
+        };
+
+        self.named
+            .get(source)
+            .map(|reader| (reader.clone(), source_path))
+            .ok_or_else(|| AssetError::UnknownSource {
+                path: path.to_string(),
+                source: source.to_string(),
+            })
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        let (reader, source_path) = self.resolve(path).ok()?;
+        let modified = reader.modified(source_path)?;
+
+        match reader.modified(&meta_path(source_path)) {
+            Some(meta_modified) => Some(modified.max(meta_modified)),
+            None => Some(modified),
+        }
+    }
+
+    fn size(&self, path: &str) -> Option<u64> {
+        let (reader, source_path) = self.resolve(path).ok()?;
+
+        reader.size(source_path)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let (reader, source_path) = self.resolve(path)?;
+
+        reader
+            .read(source_path)
+            .map_err(|err| relocate_error(err, path))
+    }
+}
+
+pub struct LoadedAsset {
+    pub handle_id: HandleId,
+    pub asset: Box<dyn Any + Send + Sync>,
+    pub size: usize,
+}
+
+struct LoadOutput {
+    loaded_asset: LoadedAsset,
+    dependencies: Vec<LoadDependency>,
+    labeled_assets: Vec<LabeledAsset>,
+}
+
+type LoadResult = (HandleId, Result<LoadOutput, AssetError>);
+
+#[derive(Clone, Copy)]
+struct AssetType {
+    id: TypeId,
+    name: &'static str,
+}
+
+impl AssetType {
+    fn of<T: 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+struct AssetInfo {
+    path: Option<String>,
+    loader: Option<Arc<dyn ErasedAssetLoader>>,
+    modified: Option<SystemTime>,
+    parent: Option<HandleId>,
+    dependencies: Vec<HandleId>,
+    dependents: Vec<HandleId>,
+}
 
 pub struct AssetServer {
-    path_to_handle_id: HashMap<String, HandleId>,
-    pending_to_load: VecDeque<String>,
+    handle_allocator: Arc<Mutex<HandleAllocator>>,
+    missing_texture: HandleId,
+    load_states: HashMap<HandleId, LoadState>,
+    load_errors: HashMap<HandleId, AssetError>,
+    asset_infos: HashMap<HandleId, AssetInfo>,
+    loading_parents: HashMap<HandleId, Arc<StrongHandle>>,
+    labeled_assets: HashMap<HandleId, Vec<Arc<StrongHandle>>>,
+    unreferenced_parents: HashSet<HandleId>,
+    reloading_dependents: HashSet<HandleId>,
+    groups: HashMap<String, AssetGroup>,
+    events: Events<AssetEvent>,
+
+    sources: AssetSources,
+
+    watch_for_changes: bool,
+    last_watch: Instant,
+
+    loaders: Vec<Arc<dyn ErasedAssetLoader>>,
+    extension_to_loaders: HashMap<String, Vec<usize>>,
+
+    job_sender: mpsc::Sender<LoadJob>,
+    result_receiver: mpsc::Receiver<LoadResult>,
+    loaded: VecDeque<LoadedAsset>,
+
+    drop_sender: mpsc::Sender<HandleId>,
+    drop_receiver: mpsc::Receiver<HandleId>,
 }
 
 impl AssetServer {
     pub fn new() -> Self {
+        let (job_sender, job_receiver) = mpsc::channel::<LoadJob>();
+        let (result_sender, result_receiver) = mpsc::channel();
+        let (drop_sender, drop_receiver) = mpsc::channel();
+
+        let mut handle_allocator = HandleAllocator::new();
+        let missing_texture = handle_allocator.allocate(Some(MISSING_TEXTURE_PATH));
+        let handle_allocator = Arc::new(Mutex::new(handle_allocator));
+
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let workers = thread::available_parallelism()
+            .map_or(1, |parallelism| parallelism.get())
+            .min(MAX_WORKERS);
+
+        for index in 0..workers {
+            let job_receiver = job_receiver.clone();
+            let result_sender = result_sender.clone();
+            let handle_allocator = handle_allocator.clone();
+            let drop_sender = drop_sender.clone();
+
+            thread::Builder::new()
+                .name(format!("AssetServer:worker_{index}"))
+                .spawn(move || loop {
+                    let job = match job_receiver.lock() {
+                        Ok(job_receiver) => job_receiver.recv(),
+                        Err(_) => return,
+                    };
+
+                    let Ok(job) = job else {
+                        return;
+                    };
+
+                    let result = run_load_job(&job, &handle_allocator, &drop_sender);
+
+                    if result_sender.send((job.handle_id, result)).is_err() {
+                        return;
+                    }
+                })
+                .expect("failed to spawn an asset worker thread");
+        }
+
         Self {
-            path_to_handle_id: HashMap::new(),
-            pending_to_load: VecDeque::new(),
+            handle_allocator,
+            missing_texture,
+            load_states: HashMap::new(),
+            load_errors: HashMap::new(),
+            asset_infos: HashMap::new(),
+            loading_parents: HashMap::new(),
+            labeled_assets: HashMap::new(),
+            unreferenced_parents: HashSet::new(),
+            reloading_dependents: HashSet::new(),
+            groups: HashMap::new(),
+            events: Events::new(),
+
+            sources: AssetSources {
+                default: default_asset_reader(),
+                named: HashMap::new(),
+            },
+
+            watch_for_changes: false,
+            last_watch: Instant::now(),
+
+            loaders: Vec::new(),
+            extension_to_loaders: HashMap::new(),
+
+            job_sender,
+            result_receiver,
+            loaded: VecDeque::new(),
+
+            drop_sender,
+            drop_receiver,
+        }
+    }
+
+    pub fn set_default_source(&mut self, reader: impl AssetReader) {
+        self.sources.default = Arc::new(reader);
+    }
+
+    pub fn register_source(&mut self, name: &str, reader: impl AssetReader) {
+        self.sources
+            .named
+            .insert(name.to_string(), Arc::new(reader));
+    }
+
+    pub fn register_loader<L: AssetLoader>(&mut self, loader: L) {
+        let index = self.loaders.len();
+
+        for extension in AssetLoader::extensions(&loader) {
+            self.extension_to_loaders
+                .entry(extension.to_lowercase())
+                .or_default()
+                .push(index);
+        }
+
+        self.loaders.push(Arc::new(loader));
+    }
+
+    pub fn load<T: Send + Sync + 'static>(&mut self, path: &str) -> Handle<T> {
+        let handle_id = self.load_path(path, Some(AssetType::of::<T>()));
+
+        Handle::strong(self.strong_handle(handle_id))
+    }
+
+    pub fn load_group(&mut self, name: &str, paths: &[&str]) {
+        let entries = paths
+            .iter()
+            .map(|path| {
+                let handle_id = self.load_path(path, None);
+                let source_path = path
+                    .split_once(LABEL_SEPARATOR)
+                    .map_or(*path, |(path, _)| path);
+
+                GroupEntry {
+                    handle: self.strong_handle(handle_id),
+                    size: self.sources.size(source_path),
+                }
+            })
+            .collect();
+
+        self.groups.insert(
+            name.to_string(),
+            AssetGroup {
+                entries,
+                finished: false,
+            },
+        );
+    }
+
+    pub fn load_group_manifest(&mut self, path: &str) -> Result<Vec<String>, AssetError> {
+        let bytes = self.sources.read(path)?;
+        let manifest: BTreeMap<String, Vec<String>> =
+            serde_json::from_slice(&bytes).map_err(|err| AssetError::Decode {
+                path: path.to_string(),
+                message: err.to_string(),
+            })?;
+
+        let mut names = Vec::with_capacity(manifest.len());
+
+        for (name, paths) in manifest {
+            let paths = paths.iter().map(String::as_str).collect::<Vec<_>>();
+
+            self.load_group(&name, &paths);
+            names.push(name);
+        }
+
+        Ok(names)
+    }
+
+    pub fn group_progress(&self, name: &str) -> Option<GroupProgress> {
+        let group = self.groups.get(name)?;
+        let mut progress = GroupProgress {
+            total: group.entries.len(),
+            ..Default::default()
+        };
+
+        for entry in &group.entries {
+            let size = entry.size.unwrap_or(0);
+            progress.total_bytes += size;
+
+            match self.get_recursive_load_state(&entry.id()) {
+                RecursiveLoadState::Loaded => {
+                    progress.loaded += 1;
+                    progress.loaded_bytes += size;
+                }
+                RecursiveLoadState::Failed => {
+                    progress.failed += 1;
+                    progress.failed_bytes += size;
+                }
+                _ => {}
+            }
+        }
+
+        Some(progress)
+    }
+
+    pub fn get_group(&self, name: &str) -> Option<Vec<HandleId>> {
+        let group = self.groups.get(name)?;
+
+        Some(group.entries.iter().map(GroupEntry::id).collect())
+    }
+
+    pub fn unload_group(&mut self, name: &str) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    pub fn update_groups(&mut self) {
+        let mut finished = Vec::new();
+
+        for (name, group) in &self.groups {
+            if group.finished {
+                continue;
+            }
+
+            if self
+                .group_progress(name)
+                .is_some_and(|progress| progress.is_finished())
+            {
+                finished.push(name.clone());
+            }
+        }
+
+        for name in finished {
+            if let Some(group) = self.groups.get_mut(&name) {
+                group.finished = true;
+            }
+
+            self.events.send(AssetEvent::GroupFinished(name));
+        }
+    }
+
+    pub fn add<T: Send + Sync + 'static>(&mut self, assets: &mut Assets<T>, asset: T) -> Handle<T> {
+        let handle_id = self.allocator().allocate(None);
+        self.track(handle_id, None, LoadState::Loaded);
+
+        assets.insert(handle_id, asset);
+        self.events.send(AssetEvent::Loaded(handle_id));
+
+        Handle::strong(self.strong_handle(handle_id))
+    }
+
+    pub fn reserve<T>(&mut self) -> Handle<T> {
+        let handle_id = self.allocator().allocate(None);
+        self.track(handle_id, None, LoadState::Loaded);
+
+        Handle::strong(self.strong_handle(handle_id))
+    }
+
+    pub fn get_handle<T>(&mut self, path: &str) -> Option<Handle<T>> {
+        let handle_id = self.allocator().get_id(path)?;
+
+        if !self.asset_infos.contains_key(&handle_id) {
+            return None;
+        }
+
+        Some(Handle::strong(self.strong_handle(handle_id)))
+    }
+
+    pub fn collect_unused(&mut self) -> Vec<HandleId> {
+        let mut unused = Vec::new();
+        let mut dropped = self.unreferenced_parents.drain().collect::<Vec<_>>();
+
+        loop {
+            dropped.extend(self.drop_receiver.try_iter());
+            let Some(handle_id) = dropped.pop() else {
+                break;
+            };
+
+            let (deallocated, waiting) = {
+                let mut handle_allocator = self.allocator();
+                let unreferenced = handle_allocator.strong_count(handle_id) == 0;
+
+                // The server holds sub-assets itself, so only handles from elsewhere count.
+                let labeled_in_use = self.labeled_assets.get(&handle_id).is_some_and(|labeled| {
+                    labeled
+                        .iter()
+                        .any(|strong_handle| handle_allocator.strong_count(strong_handle.id()) > 1)
+                });
+
+                (
+                    unreferenced && !labeled_in_use && handle_allocator.deallocate(handle_id),
+                    unreferenced && labeled_in_use,
+                )
+            };
+
+            if waiting {
+                self.unreferenced_parents.insert(handle_id);
+            }
+
+            if !deallocated {
+                continue;
+            }
+
+            let Some(asset_info) = self.asset_infos.remove(&handle_id) else {
+                continue;
+            };
+
+            self.labeled_assets.remove(&handle_id);
+            self.reloading_dependents.remove(&handle_id);
+
+            for dependency_id in asset_info.dependencies {
+                if let Some(dependency_info) = self.asset_infos.get_mut(&dependency_id) {
+                    dependency_info
+                        .dependents
+                        .retain(|&dependent_id| dependent_id != handle_id);
+                }
+            }
+
+            self.load_states.remove(&handle_id);
+            self.load_errors.remove(&handle_id);
+            self.events.send(AssetEvent::Unloaded(handle_id));
+
+            unused.push(handle_id);
+        }
+
+        unused
+    }
+
+    pub fn stats(&self) -> AssetStats {
+        let mut stats = AssetStats {
+            tracked: self.asset_infos.len(),
+            ..Default::default()
+        };
+
+        for load_state in self.load_states.values() {
+            match load_state {
+                LoadState::NotLoaded => {}
+                LoadState::Loading => stats.loading += 1,
+                LoadState::Loaded => stats.loaded += 1,
+                LoadState::Failed => stats.failed += 1,
+            }
+        }
+
+        let handle_allocator = self.allocator();
+        stats.strong_handles = self
+            .asset_infos
+            .keys()
+            .map(|&handle_id| handle_allocator.strong_count(handle_id))
+            .sum();
+        drop(handle_allocator);
+
+        stats
+    }
+
+    pub fn watch_for_changes(&mut self, enabled: bool) {
+        self.watch_for_changes = enabled;
+    }
+
+    pub fn check_for_changes(&mut self) {
+        if !self.watch_for_changes || self.last_watch.elapsed() < WATCH_INTERVAL {
+            return;
+        }
+
+        self.last_watch = Instant::now();
+
+        let mut changed = Vec::new();
+        for (handle_id, asset_info) in &mut self.asset_infos {
+            let Some(path) = &asset_info.path else {
+                continue;
+            };
+
+            let Some(modified) = self.sources.modified(path) else {
+                continue;
+            };
+
+            let previous = asset_info.modified.replace(modified);
+
+            if previous.is_some_and(|previous| previous != modified) {
+                if let Some(loader) = &asset_info.loader {
+                    changed.push((*handle_id, path.clone(), loader.clone()));
+                }
+            }
+        }
+
+        for (handle_id, path, loader) in changed {
+            log::info!("Reloading \"{path}\"");
+
+            self.dispatch(handle_id, path, loader);
+        }
+    }
+
+    pub fn regenerate_meta(&self, path: &str, reset: bool) -> Result<Option<String>, AssetError> {
+        let Some(loader) = self.find_loader(path, None) else {
+            return Ok(None);
+        };
+
+        let meta = match reset {
+            true => None,
+            false => match self.sources.read(&meta_path(path)) {
+                Ok(meta) => Some(meta),
+                Err(AssetError::NotFound(_)) => None,
+                Err(err) => return Err(err),
+            },
+        };
+
+        loader.regenerate_meta(path, meta.as_deref())
+    }
+
+    pub fn events(&mut self) -> &mut Events<AssetEvent> {
+        &mut self.events
+    }
+
+    pub fn missing_texture(&self) -> Handle<Image> {
+        Handle::weak(self.missing_texture)
+    }
+
+    pub fn get_id_by_path(&self, path: &str) -> Option<HandleId> {
+        self.allocator().get_id(path)
+    }
+
+    pub fn get_path(&self, handle_id: HandleId) -> Option<String> {
+        self.allocator().get_path(handle_id).map(str::to_string)
+    }
+
+    pub fn get_load_state(&self, handle_id: &HandleId) -> LoadState {
+        self.load_states
+            .get(handle_id)
+            .copied()
+            .unwrap_or(LoadState::NotLoaded)
+    }
+
+    pub fn get_load_error(&self, handle_id: &HandleId) -> Option<&AssetError> {
+        self.load_errors.get(handle_id)
+    }
+
+    pub fn receive_loaded(&mut self) {
+        while let Ok((handle_id, result)) = self.result_receiver.try_recv() {
+            if !self.asset_infos.contains_key(&handle_id) {
+                continue;
+            }
+
+            match result {
+                Ok(load_output) => {
+                    let mut dependency_ids = Vec::with_capacity(load_output.dependencies.len());
+                    for dependency in load_output.dependencies {
+                        dependency_ids.push(dependency.handle_id);
+                        self.load_dependency(dependency);
+                    }
+
+                    self.link_dependencies(handle_id, dependency_ids);
+
+                    self.receive_labeled_assets(handle_id, load_output.labeled_assets);
+                    self.loaded.push_back(load_output.loaded_asset);
+                    self.loading_parents.remove(&handle_id);
+                }
+                Err(err) => self.fail(handle_id, err),
+            }
         }
     }
 
-    pub fn load<T>(&mut self, path: &str) -> Handle<T> {
-        if let Some(handle_id) = self.path_to_handle_id.get(path) {
-            return Handle::new(handle_id.clone());
+    pub fn pop_loaded(&mut self) -> Option<LoadedAsset> {
+        // An asset can wait here across frames, so its last handle may have been dropped since.
+        let loaded_asset = loop {
+            let loaded_asset = self.loaded.pop_front()?;
+
+            if self.asset_infos.contains_key(&loaded_asset.handle_id) {
+                break loaded_asset;
+            }
+        };
+        let handle_id = loaded_asset.handle_id;
+
+        let modified = self.get_load_state(&handle_id) == LoadState::Loaded;
+
+        self.load_states.insert(handle_id, LoadState::Loaded);
+        self.load_errors.remove(&handle_id);
+
+        if modified {
+            self.events.send(AssetEvent::Modified(handle_id));
+
+            // Dependents reloaded by a change are already covered by it, which also breaks cycles.
+            if !self.reloading_dependents.remove(&handle_id) {
+                for dependent_id in self.transitive_dependents(handle_id) {
+                    if self.reloading_dependents.insert(dependent_id) {
+                        self.reload(dependent_id);
+                    }
+                }
+            }
+        } else {
+            self.events.send(AssetEvent::Loaded(handle_id));
         }
 
-        let handle_id = HandleId::new(path);
+        Some(loaded_asset)
+    }
 
-        self.path_to_handle_id
-            .insert(path.to_string(), handle_id.clone());
+    pub fn get_recursive_load_state(&self, handle_id: &HandleId) -> RecursiveLoadState {
+        self.recursive_load_state(*handle_id, &mut HashSet::new())
+    }
 
-        self.pending_to_load.push_back(path.to_string());
+    pub fn get_dependencies(&self, handle_id: &HandleId) -> &[HandleId] {
+        self.asset_infos
+            .get(handle_id)
+            .map_or(&[], |asset_info| &asset_info.dependencies)
+    }
 
-        Handle::new(handle_id)
+    pub fn get_dependents(&self, handle_id: &HandleId) -> &[HandleId] {
+        self.asset_infos
+            .get(handle_id)
+            .map_or(&[], |asset_info| &asset_info.dependents)
     }
 
-    pub fn get_id_by_path(&self, path: &str) -> Option<&HandleId> {
-        self.path_to_handle_id.get(path)
+    fn recursive_load_state(
+        &self,
+        handle_id: HandleId,
+        visited: &mut HashSet<HandleId>,
+    ) -> RecursiveLoadState {
+        if !visited.insert(handle_id) {
+            return RecursiveLoadState::Loaded;
+        }
+
+        let mut recursive_load_state = match self.get_load_state(&handle_id) {
+            LoadState::NotLoaded => return RecursiveLoadState::NotLoaded,
+            LoadState::Failed => return RecursiveLoadState::Failed,
+            LoadState::Loading => RecursiveLoadState::Loading,
+            LoadState::Loaded => RecursiveLoadState::Loaded,
+        };
+
+        for &dependency_id in self.get_dependencies(&handle_id) {
+            if !self.asset_infos.contains_key(&dependency_id) {
+                continue;
+            }
+
+            match self.recursive_load_state(dependency_id, visited) {
+                RecursiveLoadState::Failed => return RecursiveLoadState::Failed,
+                RecursiveLoadState::Loaded => {}
+                _ => recursive_load_state = RecursiveLoadState::Loading,
+            }
+        }
+
+        recursive_load_state
     }
 
-    pub fn get_pending_to_load(&mut self) -> Vec<String> {
-        self.pending_to_load.drain(..).collect()
+    fn transitive_dependents(&self, handle_id: HandleId) -> Vec<HandleId> {
+        let mut visited = HashSet::from([handle_id]);
+        let mut pending = vec![handle_id];
+        let mut dependents = Vec::new();
+
+        while let Some(handle_id) = pending.pop() {
+            for &dependent_id in self.get_dependents(&handle_id) {
+                if visited.insert(dependent_id) {
+                    pending.push(dependent_id);
+                    dependents.push(dependent_id);
+                }
+            }
+        }
+
+        dependents
+    }
+
+    fn link_dependencies(&mut self, handle_id: HandleId, mut dependency_ids: Vec<HandleId>) {
+        dependency_ids.sort();
+        dependency_ids.dedup();
+
+        let Some(asset_info) = self.asset_infos.get_mut(&handle_id) else {
+            return;
+        };
+
+        let previous = std::mem::replace(&mut asset_info.dependencies, dependency_ids.clone());
+
+        for dependency_id in previous {
+            if let Some(dependency_info) = self.asset_infos.get_mut(&dependency_id) {
+                dependency_info
+                    .dependents
+                    .retain(|&dependent_id| dependent_id != handle_id);
+            }
+        }
+
+        for dependency_id in dependency_ids {
+            if let Some(dependency_info) = self.asset_infos.get_mut(&dependency_id) {
+                dependency_info.dependents.push(handle_id);
+            }
+        }
+    }
+
+    fn allocator(&self) -> MutexGuard<'_, HandleAllocator> {
+        self.handle_allocator
+            .lock()
+            .expect("the handle allocator lock was poisoned")
+    }
+
+    fn track(&mut self, handle_id: HandleId, path: Option<&str>, load_state: LoadState) {
+        self.load_states.insert(handle_id, load_state);
+        self.asset_infos.insert(
+            handle_id,
+            AssetInfo {
+                path: path.map(str::to_string),
+                loader: None,
+                modified: path.and_then(|path| self.sources.modified(path)),
+                parent: None,
+                dependencies: Vec::new(),
+                dependents: Vec::new(),
+            },
+        );
+    }
+
+    fn load_path(&mut self, path: &str, asset_type: Option<AssetType>) -> HandleId {
+        let handle_id = self.allocator().get_id(path);
+        let handle_id = match handle_id {
+            Some(handle_id) if self.asset_infos.contains_key(&handle_id) => return handle_id,
+            Some(handle_id) => handle_id,
+            None => self.allocator().allocate(Some(path)),
+        };
+
+        match path.split_once(LABEL_SEPARATOR) {
+            Some((parent_path, _)) => self.start_labeled_load(handle_id, path, parent_path),
+            None => self.start_load(handle_id, path, asset_type),
+        }
+
+        handle_id
+    }
+
+    fn strong_handle(&mut self, handle_id: HandleId) -> Arc<StrongHandle> {
+        self.allocator()
+            .strong_handle(handle_id, &self.drop_sender)
+            .expect("tracked handle ids are always live")
+    }
+
+    fn start_load(&mut self, handle_id: HandleId, path: &str, asset_type: Option<AssetType>) {
+        self.track(handle_id, Some(path), LoadState::Loading);
+        let loader = self.find_loader(path, asset_type.map(|asset_type| asset_type.id));
+
+        if let Some(asset_info) = self.asset_infos.get_mut(&handle_id) {
+            asset_info.loader = loader.clone();
+        }
+
+        match loader {
+            Some(loader) => self.dispatch(handle_id, path.to_string(), loader),
+            None => self.fail(
+                handle_id,
+                AssetError::MissingLoader {
+                    path: path.to_string(),
+                    asset_type: asset_type
+                        .map_or("any asset", |asset_type| asset_type.name)
+                        .to_string(),
+                },
+            ),
+        }
+    }
+
+    fn load_dependency(&mut self, dependency: LoadDependency) {
+        if self.asset_infos.contains_key(&dependency.handle_id)
+            || self.allocator().get_path(dependency.handle_id).is_none()
+        {
+            return;
+        }
+
+        let asset_type = AssetType {
+            id: dependency.type_id,
+            name: dependency.type_name,
+        };
+
+        self.start_load(dependency.handle_id, &dependency.path, Some(asset_type));
+    }
+
+    fn start_labeled_load(&mut self, handle_id: HandleId, path: &str, parent_path: &str) {
+        let parent_id = self.allocator().get_id(parent_path);
+        let parent_id = match parent_id {
+            Some(parent_id) if self.asset_infos.contains_key(&parent_id) => parent_id,
+            Some(parent_id) => {
+                self.start_load(parent_id, parent_path, None);
+                parent_id
+            }
+            None => {
+                let parent_id = self.allocator().allocate(Some(parent_path));
+                self.start_load(parent_id, parent_path, None);
+                parent_id
+            }
+        };
+
+        self.track(handle_id, Some(path), LoadState::Loading);
+        if let Some(asset_info) = self.asset_infos.get_mut(&handle_id) {
+            asset_info.parent = Some(parent_id);
+        }
+
+        match self.get_load_state(&parent_id) {
+            LoadState::Failed => {
+                let err = self
+                    .get_load_error(&parent_id)
+                    .cloned()
+                    .unwrap_or_else(|| AssetError::NotFound(parent_path.to_string()));
+
+                self.fail(handle_id, err);
+            }
+            // Sub-assets live as long as their parent, so the last load did not produce this one.
+            LoadState::Loaded => {
+                let label = path
+                    .split_once(LABEL_SEPARATOR)
+                    .map_or("", |(_, label)| label);
+                let err = AssetError::MissingLabel {
+                    path: parent_path.to_string(),
+                    label: label.to_string(),
+                };
+
+                self.fail(handle_id, err);
+            }
+            _ => {
+                let parent_handle = self.strong_handle(parent_id);
+                self.loading_parents.insert(parent_id, parent_handle);
+            }
+        }
+    }
+
+    fn receive_labeled_assets(&mut self, parent_id: HandleId, labeled_assets: Vec<LabeledAsset>) {
+        let mut labeled_ids = Vec::with_capacity(labeled_assets.len());
+        let mut labeled_handles = Vec::with_capacity(labeled_assets.len());
+
+        for labeled_asset in labeled_assets {
+            let handle_id = labeled_asset.handle_id;
+            let Some(path) = self.allocator().get_path(handle_id).map(str::to_string) else {
+                continue;
+            };
+
+            if !self.asset_infos.contains_key(&handle_id) {
+                self.track(handle_id, Some(&path), LoadState::Loading);
+            }
+
+            if let Some(asset_info) = self.asset_infos.get_mut(&handle_id) {
+                asset_info.parent = Some(parent_id);
+            }
+
+            log::debug!("Loaded sub-asset \"{}\" of \"{path}\"", labeled_asset.label);
+
+            labeled_ids.push(handle_id);
+            labeled_handles.push(self.strong_handle(handle_id));
+            self.loaded.push_back(LoadedAsset {
+                handle_id,
+                size: self.asset_size(labeled_asset.asset.as_ref()),
+                asset: labeled_asset.asset,
+            });
+        }
+
+        self.labeled_assets.insert(parent_id, labeled_handles);
+
+        for (handle_id, path) in self.labeled_children(parent_id) {
+            if labeled_ids.contains(&handle_id) {
+                continue;
+            }
+
+            let (parent_path, label) = path.split_once(LABEL_SEPARATOR).unwrap_or((&path, ""));
+            let err = AssetError::MissingLabel {
+                path: parent_path.to_string(),
+                label: label.to_string(),
+            };
+
+            self.fail(handle_id, err);
+        }
+    }
+
+    fn asset_size(&self, asset: &(dyn Any + Send + Sync)) -> usize {
+        self.loaders
+            .iter()
+            .find(|loader| loader.asset_type_id() == asset.type_id())
+            .map_or(0, |loader| loader.size_of(asset))
+    }
+
+    fn labeled_children(&self, parent_id: HandleId) -> Vec<(HandleId, String)> {
+        self.asset_infos
+            .iter()
+            .filter(|(_, asset_info)| asset_info.parent == Some(parent_id))
+            .filter_map(|(handle_id, asset_info)| Some((*handle_id, asset_info.path.clone()?)))
+            .collect()
+    }
+
+    fn reload(&mut self, handle_id: HandleId) {
+        let Some(asset_info) = self.asset_infos.get(&handle_id) else {
+            return;
+        };
+
+        if let (Some(path), Some(loader)) = (asset_info.path.clone(), asset_info.loader.clone()) {
+            self.dispatch(handle_id, path, loader);
+        }
+    }
+
+    fn find_loader(
+        &self,
+        path: &str,
+        type_id: Option<TypeId>,
+    ) -> Option<Arc<dyn ErasedAssetLoader>> {
+        let file_name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+
+        file_name
+            .match_indices('.')
+            .filter_map(|(index, _)| self.extension_to_loaders.get(&file_name[index + 1..]))
+            .flatten()
+            .map(|&index| &self.loaders[index])
+            .find(|loader| type_id.is_none_or(|type_id| loader.asset_type_id() == type_id))
+            .cloned()
+    }
+
+    fn dispatch(&mut self, handle_id: HandleId, path: String, loader: Arc<dyn ErasedAssetLoader>) {
+        let (reader, source_path) = match self.sources.resolve(&path) {
+            Ok((reader, source_path)) => (reader, source_path.to_string()),
+            Err(err) => {
+                self.fail(handle_id, err);
+                return;
+            }
+        };
+
+        let job = LoadJob {
+            handle_id,
+            path: path.clone(),
+            source_path,
+            reader,
+            loader,
+        };
+
+        if self.job_sender.send(job).is_err() {
+            self.fail(
+                handle_id,
+                AssetError::Io {
+                    path,
+                    message: "asset workers are not running".to_string(),
+                },
+            );
+        }
+    }
+
+    fn fail(&mut self, handle_id: HandleId, err: AssetError) {
+        self.loading_parents.remove(&handle_id);
+        self.reloading_dependents.remove(&handle_id);
+
+        if self.get_load_state(&handle_id) == LoadState::Loaded {
+            log::warn!("{err}, keeping the previous version");
+
+            self.load_errors.insert(handle_id, err);
+            return;
+        }
+
+        log::error!("{err}");
+
+        self.load_states.insert(handle_id, LoadState::Failed);
+        self.load_errors.insert(handle_id, err.clone());
+        self.events.send(AssetEvent::Failed(handle_id));
+
+        for (child_id, _) in self.labeled_children(handle_id) {
+            self.fail(child_id, err.clone());
+        }
+    }
+}
+
+impl Default for AssetServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn default_asset_reader() -> Arc<dyn AssetReader> {
+    let archive = std::env::var_os(ASSET_ARCHIVE_VARIABLE)
+        .map(PathBuf::from)
+        .or_else(|| {
+            let executable = std::env::current_exe().ok()?;
+            let archive = executable
+                .parent()?
+                .join("assets")
+                .with_extension(ARCHIVE_EXTENSION);
+
+            archive.is_file().then_some(archive)
+        });
+
+    if let Some(archive) = archive {
+        match ArchiveAssetReader::open(&archive) {
+            Ok(reader) => return Arc::new(reader),
+            Err(err) => log::warn!(
+                "Failed to open asset archive \"{}\", falling back to loose files: {err}",
+                archive.display()
+            ),
+        }
+    }
+
+    Arc::new(FileAssetReader::new(default_asset_dir()))
+}
+
+fn default_asset_dir() -> PathBuf {
+    if let Some(asset_dir) = std::env::var_os(ASSET_DIR_VARIABLE) {
+        return PathBuf::from(asset_dir);
+    }
+
+    let executable_dir = std::env::current_exe()
+        .ok()
+        .and_then(|executable| Some(executable.parent()?.join("assets")));
+    let manifest_dir = option_env!("CARGO_MANIFEST_DIR")
+        .map(|manifest_dir| PathBuf::from(manifest_dir).join("assets"));
+
+    [executable_dir, manifest_dir]
+        .into_iter()
+        .flatten()
+        .find(|asset_dir| asset_dir.is_dir())
+        .unwrap_or_else(|| PathBuf::from("assets"))
+}
+
+fn run_load_job(
+    job: &LoadJob,
+    handle_allocator: &Mutex<HandleAllocator>,
+    drop_sender: &mpsc::Sender<HandleId>,
+) -> Result<LoadOutput, AssetError> {
+    let bytes = job
+        .reader
+        .read(&job.source_path)
+        .map_err(|err| relocate_error(err, &job.path))?;
+
+    let meta = match job.reader.read(&meta_path(&job.source_path)) {
+        Ok(meta) => Some(meta),
+        Err(AssetError::NotFound(_)) => None,
+        Err(err) => return Err(relocate_error(err, &meta_path(&job.path))),
+    };
+
+    let mut load_context = LoadContext::new(&job.path, handle_allocator, drop_sender);
+    let asset = job
+        .loader
+        .load(&bytes, meta.as_deref(), &mut load_context)?;
+    let (dependencies, labeled_assets) = load_context.into_parts();
+
+    Ok(LoadOutput {
+        loaded_asset: LoadedAsset {
+            handle_id: job.handle_id,
+            size: job.loader.size_of(asset.as_ref()),
+            asset,
+        },
+        dependencies,
+        labeled_assets,
+    })
+}
+
+fn relocate_error(err: AssetError, path: &str) -> AssetError {
+    match err {
+        AssetError::NotFound(_) => AssetError::NotFound(path.to_string()),
+        AssetError::Io { message, .. } => AssetError::Io {
+            path: path.to_string(),
+            message,
+        },
+        AssetError::Corrupted(_) => AssetError::Corrupted(path.to_string()),
+        err => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::core::assets::{
+        io::{EmbeddedAssetReader, MemoryAssetReader},
+        loaders::ImageLoader,
+        TextureAtlas,
+    };
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    // Loads text files, labeling a sub-asset for every line and loading lines starting with '@'.
+    struct LinesLoader;
+
+    impl AssetLoader for LinesLoader {
+        type Asset = String;
+        type Settings = ();
+
+        fn extensions(&self) -> &[&str] {
+            &["lines"]
+        }
+
+        fn load(
+            &self,
+            bytes: &[u8],
+            _settings: &Self::Settings,
+            load_context: &mut LoadContext,
+        ) -> Result<Self::Asset, AssetError> {
+            let text = String::from_utf8_lossy(bytes).into_owned();
+            for line in text.lines() {
+                match line.strip_prefix('@') {
+                    Some(path) => load_context.load::<String>(path),
+                    None => load_context.add_labeled_asset(line, line.to_string()),
+                };
+            }
+
+            Ok(text)
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image::RgbaImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+
+        bytes
+    }
+
+    fn memory_server(reader: &MemoryAssetReader) -> AssetServer {
+        let mut asset_server = AssetServer::new();
+        asset_server.set_default_source(reader.clone());
+        asset_server.register_loader(ImageLoader);
+
+        asset_server
+    }
+
+    // Pumps the server until `handle_id` is no longer loading, returning everything popped.
+    fn finish_loading(asset_server: &mut AssetServer, handle_id: HandleId) -> Vec<LoadedAsset> {
+        let deadline = Instant::now() + TIMEOUT;
+        let mut loaded = Vec::new();
+
+        while asset_server.get_load_state(&handle_id) == LoadState::Loading {
+            assert!(Instant::now() < deadline, "timed out waiting for a load");
+
+            asset_server.receive_loaded();
+            while let Some(loaded_asset) = asset_server.pop_loaded() {
+                loaded.push(loaded_asset);
+            }
+
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        loaded
+    }
+
+    #[test]
+    fn loaded_size_is_the_decoded_size() {
+        let reader = MemoryAssetReader::new();
+        let bytes = png(256, 128);
+        reader.insert("blank.png", bytes.clone());
+
+        let mut asset_server = memory_server(&reader);
+        let handle = asset_server.load::<Image>("blank.png");
+        let loaded = finish_loading(&mut asset_server, handle.id());
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].size, 256 * 128 * 4);
+        assert!(loaded[0].size > bytes.len());
+    }
+
+    #[test]
+    fn unloaded_assets_are_not_popped() {
+        let reader = MemoryAssetReader::new();
+        reader.insert("blank.png", png(4, 4));
+
+        let mut asset_server = memory_server(&reader);
+        let handle = asset_server.load::<Image>("blank.png");
+        let handle_id = handle.id();
+
+        let deadline = Instant::now() + TIMEOUT;
+        while asset_server.loaded.is_empty() {
+            assert!(Instant::now() < deadline, "timed out waiting for a load");
+
+            asset_server.receive_loaded();
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        drop(handle);
+        assert_eq!(asset_server.collect_unused(), vec![handle_id]);
+
+        assert!(asset_server.pop_loaded().is_none());
+        assert_eq!(
+            asset_server.get_load_state(&handle_id),
+            LoadState::NotLoaded
+        );
+    }
+
+    #[test]
+    fn new_labels_on_a_loaded_parent_do_not_reload_it() {
+        let reader = MemoryAssetReader::new();
+        reader.insert("sheet.lines", b"idle\nwalk".to_vec());
+
+        let mut asset_server = memory_server(&reader);
+        asset_server.register_loader(LinesLoader);
+
+        let sheet = asset_server.load::<String>("sheet.lines");
+        finish_loading(&mut asset_server, sheet.id());
+        asset_server.events().drain().for_each(drop);
+
+        let walk = asset_server.load::<String>("sheet.lines#walk");
+        let run = asset_server.load::<String>("sheet.lines#run");
+
+        assert_eq!(asset_server.get_load_state(&walk.id()), LoadState::Loaded);
+        assert_eq!(asset_server.get_load_state(&run.id()), LoadState::Failed);
+        assert!(matches!(
+            asset_server.get_load_error(&run.id()),
+            Some(AssetError::MissingLabel { .. })
+        ));
+
+        thread::sleep(Duration::from_millis(50));
+        asset_server.receive_loaded();
+
+        assert!(asset_server.pop_loaded().is_none());
+        assert!(!asset_server
+            .events()
+            .drain()
+            .any(|event| matches!(event, AssetEvent::Modified(_))));
+    }
+
+    #[test]
+    fn named_sources_are_routed_by_prefix() {
+        let reader = MemoryAssetReader::new();
+        reader.insert("hero.png", png(4, 4));
+
+        let mods = MemoryAssetReader::new();
+        mods.insert("hero.png", png(8, 2));
+
+        let mut asset_server = memory_server(&reader);
+        asset_server.register_source("mods", mods);
+
+        for (path, dimensions) in [("hero.png", (4, 4)), ("mods://hero.png", (8, 2))] {
+            let handle = asset_server.load::<Image>(path);
+            let loaded = finish_loading(&mut asset_server, handle.id());
+
+            let image = loaded[0].asset.downcast_ref::<Image>().unwrap();
+            assert_eq!(image.dimensions, dimensions, "{path}");
+        }
+
+        let handle = asset_server.load::<Image>("dlc://hero.png");
+        finish_loading(&mut asset_server, handle.id());
+
+        assert!(matches!(
+            asset_server.get_load_error(&handle.id()),
+            Some(AssetError::UnknownSource { source, .. }) if source == "dlc"
+        ));
+    }
+
+    #[test]
+    fn embedded_assets_load_through_their_source() {
+        let mut embedded = EmbeddedAssetReader::new();
+        crate::embed_asset!(embedded, "character/hurt.png");
+
+        let mut asset_server = memory_server(&MemoryAssetReader::new());
+        asset_server.register_source("embedded", embedded);
+
+        let handle = asset_server.load::<Image>("embedded://character/hurt.png");
+        let loaded = finish_loading(&mut asset_server, handle.id());
+
+        let image = loaded[0].asset.downcast_ref::<Image>().unwrap();
+        assert_eq!(image.dimensions, (320, 240));
+    }
+
+    #[test]
+    fn missing_files_report_the_requested_path() {
+        let mut asset_server = memory_server(&MemoryAssetReader::new());
+        asset_server.register_source("mods", MemoryAssetReader::new());
+
+        for path in ["missing.png", "mods://missing.png"] {
+            let handle = asset_server.load::<Image>(path);
+            finish_loading(&mut asset_server, handle.id());
+
+            assert_eq!(
+                asset_server.get_load_error(&handle.id()),
+                Some(&AssetError::NotFound(path.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn load_context_resolves_paths_next_to_the_asset() {
+        let handle_allocator = Mutex::new(HandleAllocator::new());
+        let (drop_sender, _drop_receiver) = mpsc::channel();
+
+        for (path, resolved) in [
+            ("hero.json", "hero.png"),
+            ("sheets/hero.json", "sheets/hero.png"),
+            ("mods://sheets/hero.json", "mods://sheets/hero.png"),
+            ("mods://hero.json", "mods://hero.png"),
+        ] {
+            let load_context = LoadContext::new(path, &handle_allocator, &drop_sender);
+            assert_eq!(load_context.resolve_path("hero.png"), resolved);
+        }
+    }
+
+    #[test]
+    fn reloads_propagate_through_dependency_cycles_once() {
+        let reader = MemoryAssetReader::new();
+        reader.insert("a.lines", b"@b.lines".to_vec());
+        reader.insert("b.lines", b"@a.lines".to_vec());
+
+        let mut asset_server = memory_server(&reader);
+        asset_server.register_loader(LinesLoader);
+
+        let a = asset_server.load::<String>("a.lines");
+        finish_loading(&mut asset_server, a.id());
+        let b = asset_server.get_handle::<String>("b.lines").unwrap();
+        finish_loading(&mut asset_server, b.id());
+
+        assert_eq!(asset_server.get_dependents(&a.id()), &[b.id()]);
+        assert_eq!(asset_server.get_dependents(&b.id()), &[a.id()]);
+        asset_server.events().drain().for_each(drop);
+
+        asset_server.reload(a.id());
+
+        let deadline = Instant::now() + Duration::from_millis(200);
+        while Instant::now() < deadline {
+            asset_server.receive_loaded();
+            while asset_server.pop_loaded().is_some() {}
+
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let modified = asset_server
+            .events()
+            .drain()
+            .filter_map(|event| match event {
+                AssetEvent::Modified(handle_id) => Some(handle_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(modified, vec![a.id(), b.id()]);
+    }
+
+    #[test]
+    fn sub_assets_are_unloaded_with_their_parent() {
+        let reader = MemoryAssetReader::new();
+        reader.insert("hero.png", png(64, 32));
+        reader.insert(
+            "hero.png.meta",
+            br#"{ "settings": { "atlas": { "tile_size": [32, 32], "columns": 2, "rows": 1 } } }"#
+                .to_vec(),
+        );
+
+        let mut asset_server = memory_server(&reader);
+        let atlas = asset_server.load::<TextureAtlas>("hero.png#atlas");
+        finish_loading(&mut asset_server, atlas.id());
+
+        let hero = asset_server.get_id_by_path("hero.png").unwrap();
+        let mut family = [
+            "hero.png",
+            "hero.png#atlas",
+            "hero.png#frame_0",
+            "hero.png#frame_1",
+        ]
+        .map(|path| asset_server.get_id_by_path(path).unwrap())
+        .to_vec();
+        family.sort();
+
+        assert!(asset_server.collect_unused().is_empty());
+        assert_eq!(asset_server.get_load_state(&hero), LoadState::Loaded);
+
+        drop(atlas);
+        let mut unused = asset_server.collect_unused();
+        unused.sort();
+
+        assert_eq!(unused, family);
+        assert_eq!(asset_server.stats().tracked, 0);
     }
 }
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssetStats {
+    pub tracked: usize,
+    pub loading: usize,
+    pub loaded: usize,
+    pub failed: usize,
+    pub strong_handles: usize,
+}
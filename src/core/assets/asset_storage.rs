@@ -0,0 +1,129 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::core::utils::HandleId;
+
+use super::Assets;
+
+pub trait AnyAssets {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn insert_boxed(&mut self, handle_id: HandleId, asset: Box<dyn Any + Send + Sync>);
+    fn remove(&mut self, handle_id: &HandleId) -> bool;
+    fn count(&self) -> usize;
+    fn type_name(&self) -> &'static str;
+}
+
+impl<T: Send + Sync + 'static> AnyAssets for Assets<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn insert_boxed(&mut self, handle_id: HandleId, asset: Box<dyn Any + Send + Sync>) {
+        if let Ok(asset) = asset.downcast::<T>() {
+            self.insert(handle_id, *asset);
+        }
+    }
+
+    fn remove(&mut self, handle_id: &HandleId) -> bool {
+        Assets::remove(self, handle_id).is_some()
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+pub struct AssetStorage {
+    assets: HashMap<TypeId, Box<dyn AnyAssets>>,
+}
+
+impl AssetStorage {
+    pub fn new() -> Self {
+        Self {
+            assets: HashMap::new(),
+        }
+    }
+
+    pub fn register<T: Send + Sync + 'static>(&mut self) {
+        let type_id = TypeId::of::<T>();
+
+        if self.assets.contains_key(&type_id) {
+            panic!(
+                "Asset \"{}\" was already registered!",
+                std::any::type_name::<T>()
+            );
+        }
+
+        self.assets.insert(type_id, Box::new(Assets::<T>::new()));
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> &Assets<T> {
+        let Some(assets) = self
+            .assets
+            .get(&TypeId::of::<T>())
+            .and_then(|assets| assets.as_any().downcast_ref::<Assets<T>>())
+        else {
+            panic!(
+                "Trying to access an asset that was not registered: '{}'",
+                std::any::type_name::<T>()
+            )
+        };
+
+        assets
+    }
+
+    pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> &mut Assets<T> {
+        let Some(assets) = self
+            .assets
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|assets| assets.as_any_mut().downcast_mut::<Assets<T>>())
+        else {
+            panic!(
+                "Trying to access an asset that was not registered: '{}'",
+                std::any::type_name::<T>()
+            )
+        };
+
+        assets
+    }
+
+    pub fn insert_boxed(&mut self, handle_id: HandleId, asset: Box<dyn Any + Send + Sync>) -> bool {
+        let Some(assets) = self.assets.get_mut(&(*asset).type_id()) else {
+            return false;
+        };
+
+        assets.insert_boxed(handle_id, asset);
+
+        true
+    }
+
+    pub fn remove(&mut self, handle_id: &HandleId) -> bool {
+        self.assets
+            .values_mut()
+            .any(|assets| assets.remove(handle_id))
+    }
+
+    pub fn live_counts(&self) -> Vec<(&'static str, usize)> {
+        self.assets
+            .values()
+            .map(|assets| (assets.type_name(), assets.count()))
+            .collect()
+    }
+}
+
+impl Default for AssetStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,15 +1,49 @@
-use crate::core::utils::{Cache, HandleId};
+use std::sync::Arc;
 
-use super::Image;
+use crate::core::utils::{Cache, HandleId};
 
-pub struct Assets {
-    pub images: Cache<HandleId, Image>,
+pub struct Assets<T> {
+    items: Cache<HandleId, T>,
 }
 
-impl Assets {
+impl<T> Assets<T> {
     pub fn new() -> Self {
         Self {
-            images: Cache::new(),
+            items: Cache::new(),
         }
     }
+
+    pub fn insert(&mut self, handle_id: HandleId, asset: T) {
+        self.items.insert(handle_id, asset);
+    }
+
+    pub fn remove(&mut self, handle_id: &HandleId) -> Option<Arc<T>> {
+        self.items.remove(handle_id)
+    }
+
+    pub fn contains(&self, handle_id: &HandleId) -> bool {
+        self.items.exists(handle_id)
+    }
+
+    pub fn get(&self, handle_id: &HandleId) -> Option<&Arc<T>> {
+        self.items.get(handle_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&HandleId, &Arc<T>)> {
+        self.items.iter()
+    }
+}
+
+impl<T> Default for Assets<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
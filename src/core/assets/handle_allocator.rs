@@ -0,0 +1,125 @@
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc, Weak},
+};
+
+use crate::core::utils::{HandleId, StrongHandle};
+
+struct Slot {
+    generation: u32,
+    path: Option<String>,
+    handle: Weak<StrongHandle>,
+}
+
+pub struct HandleAllocator {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    path_to_handle_id: HashMap<String, HandleId>,
+}
+
+impl HandleAllocator {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            path_to_handle_id: HashMap::new(),
+        }
+    }
+
+    pub fn allocate(&mut self, path: Option<&str>) -> HandleId {
+        let handle_id = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.path = path.map(str::to_string);
+                slot.handle = Weak::new();
+
+                HandleId::new(index, slot.generation)
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("ran out of handle indices");
+                self.slots.push(Slot {
+                    generation: 0,
+                    path: path.map(str::to_string),
+                    handle: Weak::new(),
+                });
+
+                HandleId::new(index, 0)
+            }
+        };
+
+        if let Some(path) = path {
+            self.path_to_handle_id.insert(path.to_string(), handle_id);
+        }
+
+        handle_id
+    }
+
+    pub fn deallocate(&mut self, handle_id: HandleId) -> bool {
+        let Some(slot) = self.slots.get_mut(handle_id.index() as usize) else {
+            return false;
+        };
+
+        if slot.generation != handle_id.generation() {
+            return false;
+        }
+
+        slot.handle = Weak::new();
+
+        if let Some(path) = slot.path.take() {
+            self.path_to_handle_id.remove(&path);
+        }
+
+        slot.generation += 1;
+        self.free.push(handle_id.index());
+
+        true
+    }
+
+    pub fn strong_handle(
+        &mut self,
+        handle_id: HandleId,
+        drop_sender: &mpsc::Sender<HandleId>,
+    ) -> Option<Arc<StrongHandle>> {
+        let slot = self.slots.get_mut(handle_id.index() as usize)?;
+
+        if slot.generation != handle_id.generation() {
+            return None;
+        }
+
+        if let Some(strong_handle) = slot.handle.upgrade() {
+            return Some(strong_handle);
+        }
+
+        let strong_handle = Arc::new(StrongHandle::new(handle_id, drop_sender.clone()));
+        slot.handle = Arc::downgrade(&strong_handle);
+
+        Some(strong_handle)
+    }
+
+    pub fn strong_count(&self, handle_id: HandleId) -> usize {
+        self.slots
+            .get(handle_id.index() as usize)
+            .filter(|slot| slot.generation == handle_id.generation())
+            .map_or(0, |slot| slot.handle.strong_count())
+    }
+
+    pub fn get_id(&self, path: &str) -> Option<HandleId> {
+        self.path_to_handle_id.get(path).copied()
+    }
+
+    pub fn get_path(&self, handle_id: HandleId) -> Option<&str> {
+        let slot = self.slots.get(handle_id.index() as usize)?;
+
+        if slot.generation != handle_id.generation() {
+            return None;
+        }
+
+        slot.path.as_deref()
+    }
+}
+
+impl Default for HandleAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
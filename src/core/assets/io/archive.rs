@@ -0,0 +1,459 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use flate2::{read::DeflateDecoder, write::DeflateEncoder};
+
+use crate::core::assets::AssetError;
+
+use super::AssetReader;
+
+pub const ARCHIVE_EXTENSION: &str = "corvus";
+
+const MAGIC: &[u8; 4] = b"CRVA";
+const VERSION: u16 = 1;
+const HEADER_SIZE: u64 = 20;
+const MIN_INDEX_ENTRY_SIZE: u64 = 31;
+const MAX_DEFLATE_RATIO: u64 = 1032;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Deflate,
+}
+
+impl Compression {
+    fn from_byte(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Deflate),
+            _ => Err(invalid_data(format!("unknown compression method {byte}"))),
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Deflate => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub offset: u64,
+    pub stored_size: u64,
+    pub size: u64,
+    pub compression: Compression,
+    pub checksum: u32,
+}
+
+pub struct ArchiveWriter<W: Write + Seek> {
+    writer: W,
+    compression: Compression,
+    entries: Vec<(String, ArchiveEntry)>,
+    offset: u64,
+}
+
+impl ArchiveWriter<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, compression: Compression) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), compression)
+    }
+}
+
+impl<W: Write + Seek> ArchiveWriter<W> {
+    pub fn new(mut writer: W, compression: Compression) -> io::Result<Self> {
+        writer.write_all(&[0; HEADER_SIZE as usize])?;
+
+        Ok(Self {
+            writer,
+            compression,
+            entries: Vec::new(),
+            offset: HEADER_SIZE,
+        })
+    }
+
+    pub fn add(&mut self, path: &str, bytes: &[u8]) -> io::Result<&ArchiveEntry> {
+        let path = path.replace('\\', "/");
+        if u16::try_from(path.len()).is_err() {
+            return Err(invalid_data(format!("the path \"{path}\" is too long")));
+        }
+
+        let compressed = match self.compression {
+            Compression::None => None,
+            Compression::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+                encoder.write_all(bytes)?;
+
+                Some(encoder.finish()?).filter(|compressed| compressed.len() < bytes.len())
+            }
+        };
+
+        let (stored, compression) = match &compressed {
+            Some(compressed) => (compressed.as_slice(), Compression::Deflate),
+            None => (bytes, Compression::None),
+        };
+
+        self.writer.write_all(stored)?;
+
+        let entry = ArchiveEntry {
+            offset: self.offset,
+            stored_size: stored.len() as u64,
+            size: bytes.len() as u64,
+            compression,
+            checksum: crc32fast::hash(bytes),
+        };
+
+        self.offset += entry.stored_size;
+        self.entries.push((path, entry));
+
+        Ok(&self.entries[self.entries.len() - 1].1)
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        let index_offset = self.offset;
+        let entry_count = u32::try_from(self.entries.len())
+            .map_err(|_| invalid_data("too many entries".to_string()))?;
+
+        for (path, entry) in &self.entries {
+            self.writer.write_all(&(path.len() as u16).to_le_bytes())?;
+            self.writer.write_all(path.as_bytes())?;
+            self.writer.write_all(&entry.offset.to_le_bytes())?;
+            self.writer.write_all(&entry.stored_size.to_le_bytes())?;
+            self.writer.write_all(&entry.size.to_le_bytes())?;
+            self.writer.write_all(&[entry.compression.to_byte()])?;
+            self.writer.write_all(&entry.checksum.to_le_bytes())?;
+        }
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(MAGIC)?;
+        self.writer.write_all(&VERSION.to_le_bytes())?;
+        self.writer.write_all(&0u16.to_le_bytes())?;
+        self.writer.write_all(&entry_count.to_le_bytes())?;
+        self.writer.write_all(&index_offset.to_le_bytes())?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+pub fn pack_directory(
+    source: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    compression: Compression,
+) -> io::Result<Vec<(String, ArchiveEntry)>> {
+    let source = source.as_ref();
+    let output = output.as_ref();
+
+    let files = list_files(source)?;
+    let output_path = output.canonicalize().ok();
+    let mut writer = ArchiveWriter::create(output, compression)?;
+    let mut entries = Vec::new();
+
+    for file in files {
+        if file.canonicalize().ok() == output_path {
+            continue;
+        }
+
+        let relative_path = file
+            .strip_prefix(source)
+            .map_err(|err| invalid_data(err.to_string()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let entry = writer.add(&relative_path, &std::fs::read(&file)?)?.clone();
+        entries.push((relative_path, entry));
+    }
+
+    writer.finish()?;
+
+    Ok(entries)
+}
+
+pub fn list_files(directory: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    collect_files(directory.as_ref(), &mut files)?;
+    files.sort();
+
+    Ok(files)
+}
+
+fn collect_files(directory: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+pub struct ArchiveAssetReader {
+    file: Mutex<File>,
+    entries: HashMap<String, ArchiveEntry>,
+}
+
+impl ArchiveAssetReader {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut header = [0; HEADER_SIZE as usize];
+        file.read_exact(&mut header)?;
+
+        if &header[0..4] != MAGIC {
+            return Err(invalid_data("not a corvus archive".to_string()));
+        }
+
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported archive version {version}"
+            )));
+        }
+
+        let entry_count = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        let index_offset = u64::from_le_bytes(header[12..20].try_into().unwrap());
+
+        if index_offset < HEADER_SIZE || index_offset > file_len {
+            return Err(invalid_data(format!(
+                "the archive index offset {index_offset} is out of bounds"
+            )));
+        }
+
+        if entry_count as u64 * MIN_INDEX_ENTRY_SIZE > file_len - index_offset {
+            return Err(invalid_data("the archive index is truncated".to_string()));
+        }
+
+        let mut index = Vec::new();
+        file.seek(SeekFrom::Start(index_offset))?;
+        file.read_to_end(&mut index)?;
+
+        let mut cursor = index.as_slice();
+        let mut entries = HashMap::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let path_len = u16::from_le_bytes(take(&mut cursor)?) as usize;
+            let path = String::from_utf8(take_slice(&mut cursor, path_len)?.to_vec())
+                .map_err(|err| invalid_data(err.to_string()))?;
+
+            let entry = ArchiveEntry {
+                offset: u64::from_le_bytes(take(&mut cursor)?),
+                stored_size: u64::from_le_bytes(take(&mut cursor)?),
+                size: u64::from_le_bytes(take(&mut cursor)?),
+                compression: Compression::from_byte(take::<1>(&mut cursor)?[0])?,
+                checksum: u32::from_le_bytes(take(&mut cursor)?),
+            };
+
+            validate_entry(&path, &entry, index_offset)?;
+            entries.insert(path, entry);
+        }
+
+        Ok(Self {
+            file: Mutex::new(file),
+            entries,
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &ArchiveEntry)> {
+        self.entries.iter()
+    }
+
+    fn read_entry(&self, entry: &ArchiveEntry) -> io::Result<Vec<u8>> {
+        let mut stored = vec![0; entry.stored_size as usize];
+        {
+            let mut file = self
+                .file
+                .lock()
+                .map_err(|err| io::Error::other(err.to_string()))?;
+
+            file.seek(SeekFrom::Start(entry.offset))?;
+            file.read_exact(&mut stored)?;
+        }
+
+        match entry.compression {
+            Compression::None => Ok(stored),
+            Compression::Deflate => {
+                let mut bytes = Vec::with_capacity(entry.size as usize);
+                DeflateDecoder::new(stored.as_slice())
+                    .take(entry.size)
+                    .read_to_end(&mut bytes)?;
+
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+impl AssetReader for ArchiveAssetReader {
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let entry = self
+            .entries
+            .get(path)
+            .ok_or_else(|| AssetError::NotFound(path.to_string()))?;
+
+        let bytes = self.read_entry(entry).map_err(|err| AssetError::Io {
+            path: path.to_string(),
+            message: err.to_string(),
+        })?;
+
+        if bytes.len() as u64 != entry.size || crc32fast::hash(&bytes) != entry.checksum {
+            return Err(AssetError::Corrupted(path.to_string()));
+        }
+
+        Ok(bytes)
+    }
+
+    fn size(&self, path: &str) -> Option<u64> {
+        self.entries.get(path).map(|entry| entry.size)
+    }
+}
+
+fn validate_entry(path: &str, entry: &ArchiveEntry, index_offset: u64) -> io::Result<()> {
+    let in_bounds = entry.offset >= HEADER_SIZE
+        && entry
+            .offset
+            .checked_add(entry.stored_size)
+            .is_some_and(|end| end <= index_offset);
+
+    if !in_bounds {
+        return Err(invalid_data(format!(
+            "the entry \"{path}\" lies outside the archive data"
+        )));
+    }
+
+    let max_size = match entry.compression {
+        Compression::None => entry.stored_size,
+        Compression::Deflate => entry.stored_size.saturating_mul(MAX_DEFLATE_RATIO),
+    };
+
+    if entry.size > max_size {
+        return Err(invalid_data(format!(
+            "the entry \"{path}\" has an impossible size of {} bytes",
+            entry.size
+        )));
+    }
+
+    Ok(())
+}
+
+fn take<const N: usize>(cursor: &mut &[u8]) -> io::Result<[u8; N]> {
+    Ok(take_slice(cursor, N)?.try_into().unwrap())
+}
+
+fn take_slice<'a>(cursor: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if cursor.len() < len {
+        return Err(invalid_data("the archive index is truncated".to_string()));
+    }
+
+    let (bytes, rest) = cursor.split_at(len);
+    *cursor = rest;
+
+    Ok(bytes)
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    struct TempArchive(PathBuf);
+
+    impl TempArchive {
+        fn new(name: &str, bytes: &[u8]) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("{name}-{}.{ARCHIVE_EXTENSION}", std::process::id()));
+            std::fs::write(&path, bytes).unwrap();
+
+            Self(path)
+        }
+    }
+
+    impl Drop for TempArchive {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn archive(compression: Compression, files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ArchiveWriter::new(Cursor::new(Vec::new()), compression).unwrap();
+        for (path, bytes) in files {
+            writer.add(path, bytes).unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn reads_back_what_was_written() {
+        let repeated = b"corvus ".repeat(64);
+        let files: [(&str, &[u8]); 2] = [("a.txt", b"hello"), ("dir/b.txt", &repeated)];
+
+        for compression in [Compression::None, Compression::Deflate] {
+            let file = TempArchive::new("round-trip", &archive(compression, &files));
+            let reader = ArchiveAssetReader::open(&file.0).unwrap();
+
+            for (path, bytes) in files {
+                assert_eq!(reader.read(path).unwrap(), bytes);
+                assert_eq!(reader.size(path), Some(bytes.len() as u64));
+            }
+
+            assert!(matches!(
+                reader.read("missing.txt"),
+                Err(AssetError::NotFound(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn flipped_byte_is_reported_as_corrupted() {
+        let mut bytes = archive(Compression::None, &[("a.txt", b"hello")]);
+        bytes[HEADER_SIZE as usize] ^= 0xff;
+
+        let file = TempArchive::new("flipped-byte", &bytes);
+        let reader = ArchiveAssetReader::open(&file.0).unwrap();
+
+        assert!(matches!(
+            reader.read("a.txt"),
+            Err(AssetError::Corrupted(_))
+        ));
+    }
+
+    #[test]
+    fn truncated_index_is_an_error() {
+        let bytes = archive(Compression::None, &[("a.txt", b"hello"), ("b.txt", b"!")]);
+
+        for len in [HEADER_SIZE as usize + 5, bytes.len() - 1] {
+            let file = TempArchive::new("truncated-index", &bytes[..len]);
+            assert!(ArchiveAssetReader::open(&file.0).is_err());
+        }
+    }
+
+    #[test]
+    fn implausible_index_is_an_error_instead_of_an_allocation() {
+        let mut bytes = archive(Compression::Deflate, &[("a.txt", &[0; 256])]);
+        bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let file = TempArchive::new("entry-count", &bytes);
+        assert!(ArchiveAssetReader::open(&file.0).is_err());
+
+        let mut bytes = archive(Compression::None, &[("a.txt", b"hello")]);
+        let stored_size = bytes.len() - 4 - 1 - 8 - 8;
+        bytes[stored_size..stored_size + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        let file = TempArchive::new("stored-size", &bytes);
+        assert!(ArchiveAssetReader::open(&file.0).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+use std::time::SystemTime;
+
+use crate::core::assets::AssetError;
+
+pub trait AssetReader: Send + Sync + 'static {
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError>;
+
+    fn modified(&self, _path: &str) -> Option<SystemTime> {
+        None
+    }
+
+    fn size(&self, _path: &str) -> Option<u64> {
+        None
+    }
+}
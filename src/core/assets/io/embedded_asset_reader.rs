@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use crate::core::assets::AssetError;
+
+use super::AssetReader;
+
+#[macro_export]
+macro_rules! embed_asset {
+    ($reader:expr, $path:literal) => {
+        $reader.insert(
+            $path,
+            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/", $path)),
+        )
+    };
+}
+
+#[derive(Default)]
+pub struct EmbeddedAssetReader {
+    files: HashMap<&'static str, &'static [u8]>,
+}
+
+impl EmbeddedAssetReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, path: &'static str, bytes: &'static [u8]) {
+        self.files.insert(path, bytes);
+    }
+}
+
+impl AssetReader for EmbeddedAssetReader {
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        self.files
+            .get(path)
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(|| AssetError::NotFound(path.to_string()))
+    }
+
+    fn size(&self, path: &str) -> Option<u64> {
+        self.files.get(path).map(|bytes| bytes.len() as u64)
+    }
+}
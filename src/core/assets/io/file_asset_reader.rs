@@ -0,0 +1,46 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::core::assets::AssetError;
+
+use super::AssetReader;
+
+pub struct FileAssetReader {
+    root: PathBuf,
+}
+
+impl FileAssetReader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl AssetReader for FileAssetReader {
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        std::fs::read(self.root.join(path)).map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => AssetError::NotFound(path.to_string()),
+            _ => AssetError::Io {
+                path: path.to_string(),
+                message: err.to_string(),
+            },
+        })
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        std::fs::metadata(self.root.join(path))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn size(&self, path: &str) -> Option<u64> {
+        std::fs::metadata(self.root.join(path))
+            .map(|metadata| metadata.len())
+            .ok()
+    }
+}
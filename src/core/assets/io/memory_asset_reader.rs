@@ -0,0 +1,60 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use crate::core::assets::AssetError;
+
+use super::AssetReader;
+
+type MemoryFiles = HashMap<String, (Arc<[u8]>, SystemTime)>;
+
+#[derive(Clone, Default)]
+pub struct MemoryAssetReader {
+    files: Arc<RwLock<MemoryFiles>>,
+}
+
+impl MemoryAssetReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, path: &str, bytes: impl Into<Arc<[u8]>>) {
+        if let Ok(mut files) = self.files.write() {
+            files.insert(path.to_string(), (bytes.into(), SystemTime::now()));
+        }
+    }
+
+    pub fn remove(&self, path: &str) -> bool {
+        self.files
+            .write()
+            .is_ok_and(|mut files| files.remove(path).is_some())
+    }
+}
+
+impl AssetReader for MemoryAssetReader {
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let files = self.files.read().map_err(|err| AssetError::Io {
+            path: path.to_string(),
+            message: err.to_string(),
+        })?;
+
+        files
+            .get(path)
+            .map(|(bytes, _)| bytes.to_vec())
+            .ok_or_else(|| AssetError::NotFound(path.to_string()))
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        let files = self.files.read().ok()?;
+
+        files.get(path).map(|(_, modified)| *modified)
+    }
+
+    fn size(&self, path: &str) -> Option<u64> {
+        let files = self.files.read().ok()?;
+
+        files.get(path).map(|(bytes, _)| bytes.len() as u64)
+    }
+}
@@ -0,0 +1,14 @@
+mod archive;
+mod asset_reader;
+mod embedded_asset_reader;
+mod file_asset_reader;
+mod memory_asset_reader;
+
+pub use archive::{
+    list_files, pack_directory, ArchiveAssetReader, ArchiveEntry, ArchiveWriter, Compression,
+    ARCHIVE_EXTENSION,
+};
+pub use asset_reader::AssetReader;
+pub use embedded_asset_reader::EmbeddedAssetReader;
+pub use file_asset_reader::FileAssetReader;
+pub use memory_asset_reader::MemoryAssetReader;
@@ -0,0 +1,54 @@
+use crate::core::assets::{
+    AssetError, AssetLoader, Image, ImageSettings, LoadContext, TextureAtlas,
+};
+
+pub struct ImageLoader;
+
+impl AssetLoader for ImageLoader {
+    type Asset = Image;
+    type Settings = ImageSettings;
+
+    fn extensions(&self) -> &[&str] {
+        &["png", "jpg", "jpeg", "bmp", "gif", "tga", "webp"]
+    }
+
+    fn size_of(&self, image: &Self::Asset) -> usize {
+        image.size_in_bytes()
+    }
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        settings: &Self::Settings,
+        load_context: &mut LoadContext,
+    ) -> Result<Self::Asset, AssetError> {
+        let image = Image::from_bytes(load_context.path(), bytes)?.with_settings(settings.clone());
+
+        if let Some(grid) = settings.atlas {
+            let frames = grid.rects();
+
+            for (index, &frame) in frames.iter().enumerate() {
+                let label = format!("frame_{index}");
+                let path = format!("{}#{label}", load_context.path());
+
+                let Some(frame_image) = image.crop(&path, frame) else {
+                    return Err(AssetError::Decode {
+                        path: load_context.path().to_string(),
+                        message: format!(
+                            "atlas frame {index} lies outside the {}x{} image",
+                            image.dimensions.0, image.dimensions.1
+                        ),
+                    });
+                };
+
+                load_context.add_labeled_asset(&label, frame_image);
+            }
+
+            // A strong handle would keep the image alive through its own sub-asset.
+            let atlas = TextureAtlas::from_rects(load_context.handle().clone_weak(), frames);
+            load_context.add_labeled_asset("atlas", atlas);
+        }
+
+        Ok(image)
+    }
+}
@@ -0,0 +1,5 @@
+mod image_loader;
+mod sprite_sheet_loader;
+
+pub use image_loader::ImageLoader;
+pub use sprite_sheet_loader::SpriteSheetLoader;
@@ -0,0 +1,303 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::Deserialize;
+
+use crate::core::{
+    assets::{
+        AnimationClip, AnimationDirection, AssetError, AssetLoader, Image, LoadContext,
+        TextureAtlas,
+    },
+    render::Rect,
+};
+
+const DEFAULT_FRAME_DURATION: Duration = Duration::from_millis(100);
+
+#[derive(Deserialize)]
+struct SheetRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct SheetFrame {
+    #[serde(default)]
+    filename: Option<String>,
+    frame: SheetRect,
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    duration: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SheetFrames {
+    Array(Vec<SheetFrame>),
+    Hash(serde_json::Map<String, serde_json::Value>),
+}
+
+#[derive(Deserialize)]
+struct FrameTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: Option<String>,
+    #[serde(default)]
+    repeat: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SheetMeta {
+    image: String,
+    #[serde(default)]
+    frame_tags: Vec<FrameTag>,
+}
+
+#[derive(Deserialize)]
+struct Sheet {
+    frames: SheetFrames,
+    meta: SheetMeta,
+}
+
+pub struct SpriteSheetLoader;
+
+impl AssetLoader for SpriteSheetLoader {
+    type Asset = TextureAtlas;
+    type Settings = ();
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext,
+    ) -> Result<Self::Asset, AssetError> {
+        let decode_error = |message: String| AssetError::Decode {
+            path: load_context.path().to_string(),
+            message,
+        };
+
+        let sheet: Sheet =
+            serde_json::from_slice(bytes).map_err(|err| decode_error(err.to_string()))?;
+
+        let frames = match sheet.frames {
+            SheetFrames::Array(frames) => frames,
+            SheetFrames::Hash(frames) => frames
+                .into_iter()
+                .map(|(name, frame)| {
+                    let mut frame: SheetFrame = serde_json::from_value(frame)
+                        .map_err(|err| decode_error(format!("frame \"{name}\": {err}")))?;
+                    frame.filename = Some(name);
+
+                    Ok(frame)
+                })
+                .collect::<Result<Vec<_>, AssetError>>()?,
+        };
+
+        if let Some(frame) = frames.iter().find(|frame| frame.rotated) {
+            return Err(decode_error(format!(
+                "frame \"{}\" is rotated, which is not supported",
+                frame.filename.as_deref().unwrap_or_default()
+            )));
+        }
+
+        let frame_durations = frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .duration
+                    .map_or(DEFAULT_FRAME_DURATION, Duration::from_millis)
+            })
+            .collect::<Vec<_>>();
+
+        let clips = sheet
+            .meta
+            .frame_tags
+            .into_iter()
+            .map(|tag| {
+                if tag.from > tag.to || tag.to >= frames.len() {
+                    return Err(decode_error(format!(
+                        "tag \"{}\" refers to frames {}..={} but the sheet has {} frames",
+                        tag.name,
+                        tag.from,
+                        tag.to,
+                        frames.len()
+                    )));
+                }
+
+                let direction = match tag.direction.as_deref() {
+                    None | Some("forward") => AnimationDirection::Forward,
+                    Some("reverse") => AnimationDirection::Reverse,
+                    Some("pingpong") => AnimationDirection::PingPong,
+                    Some("pingpong_reverse") => AnimationDirection::PingPongReverse,
+                    Some(direction) => {
+                        return Err(decode_error(format!(
+                            "tag \"{}\" has an unknown direction \"{direction}\"",
+                            tag.name
+                        )))
+                    }
+                };
+
+                let clip = AnimationClip {
+                    frames: (tag.from..=tag.to).collect(),
+                    frame_durations: frame_durations[tag.from..=tag.to].to_vec(),
+                    direction,
+                    repeat: tag
+                        .repeat
+                        .and_then(|repeat| repeat.parse().ok())
+                        .filter(|&repeat| repeat > 0),
+                };
+
+                Ok((tag.name, clip))
+            })
+            .collect::<Result<HashMap<_, _>, AssetError>>()?;
+
+        let frame_names = frames
+            .iter()
+            .enumerate()
+            .filter_map(|(index, frame)| Some((frame.filename.clone()?, index)))
+            .collect();
+
+        let rects = frames
+            .iter()
+            .map(|frame| Rect::new(frame.frame.x, frame.frame.y, frame.frame.w, frame.frame.h))
+            .collect();
+
+        let image_path = load_context.resolve_path(&sheet.meta.image);
+        let image = load_context.load::<Image>(&image_path);
+
+        for (name, clip) in &clips {
+            load_context.add_labeled_asset(name, clip.clone());
+        }
+
+        let mut atlas = TextureAtlas::from_rects(image, rects);
+        atlas.frame_names = frame_names;
+        atlas.clips = clips;
+
+        Ok(atlas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Mutex};
+
+    use crate::core::assets::handle_allocator::HandleAllocator;
+
+    use super::*;
+
+    // Loads `json` as "sheets/hero.json", returning the atlas and its dependency paths.
+    fn load(json: &str) -> Result<(TextureAtlas, Vec<String>), AssetError> {
+        let handle_allocator = Mutex::new(HandleAllocator::new());
+        let (drop_sender, _drop_receiver) = mpsc::channel();
+        let mut load_context =
+            LoadContext::new("sheets/hero.json", &handle_allocator, &drop_sender);
+
+        let atlas = SpriteSheetLoader.load(json.as_bytes(), &(), &mut load_context)?;
+        let (dependencies, _) = load_context.into_parts();
+
+        Ok((
+            atlas,
+            dependencies
+                .into_iter()
+                .map(|dependency| dependency.path)
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn parses_aseprite_hash_sheets() {
+        let (atlas, dependencies) = load(
+            r#"{
+                "frames": {
+                    "hero 0.aseprite": { "frame": { "x": 0, "y": 0, "w": 16, "h": 24 }, "duration": 80 },
+                    "hero 1.aseprite": { "frame": { "x": 16, "y": 0, "w": 16, "h": 24 }, "duration": 120 },
+                    "hero 2.aseprite": { "frame": { "x": 32, "y": 0, "w": 16, "h": 24 } }
+                },
+                "meta": {
+                    "image": "hero.png",
+                    "frameTags": [
+                        { "name": "walk", "from": 0, "to": 1, "direction": "pingpong", "repeat": "2" },
+                        { "name": "idle", "from": 2, "to": 2 }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(dependencies, vec!["sheets/hero.png".to_string()]);
+        assert_eq!(atlas.frame(1), Some(Rect::new(16, 0, 16, 24)));
+        assert_eq!(atlas.frame_index("hero 2.aseprite"), Some(2));
+
+        assert_eq!(
+            atlas.clip("walk"),
+            Some(&AnimationClip {
+                frames: vec![0, 1],
+                frame_durations: vec![Duration::from_millis(80), Duration::from_millis(120)],
+                direction: AnimationDirection::PingPong,
+                repeat: Some(2),
+            })
+        );
+        assert_eq!(
+            atlas.clip("idle"),
+            Some(&AnimationClip {
+                frames: vec![2],
+                frame_durations: vec![DEFAULT_FRAME_DURATION],
+                direction: AnimationDirection::Forward,
+                repeat: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_texture_packer_array_sheets() {
+        let (atlas, dependencies) = load(
+            r#"{
+                "frames": [
+                    { "filename": "sword.png", "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "rotated": false },
+                    { "filename": "shield.png", "frame": { "x": 8, "y": 0, "w": 12, "h": 10 } }
+                ],
+                "meta": { "image": "../items.png", "size": { "w": 32, "h": 16 } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(dependencies, vec!["sheets/../items.png".to_string()]);
+        assert_eq!(atlas.len(), 2);
+        assert_eq!(atlas.frame_index("shield.png"), Some(1));
+        assert_eq!(atlas.frame(1), Some(Rect::new(8, 0, 12, 10)));
+        assert!(atlas.clips.is_empty());
+    }
+
+    #[test]
+    fn rejects_sheets_it_cannot_represent() {
+        let frame = r#"{ "frame": { "x": 0, "y": 0, "w": 8, "h": 8 } }"#;
+        let rotated = r#"{ "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "rotated": true }"#;
+
+        for json in [
+            format!(r#"{{ "frames": [{rotated}], "meta": {{ "image": "a.png" }} }}"#),
+            format!(
+                r#"{{ "frames": [{frame}], "meta": {{ "image": "a.png",
+                    "frameTags": [{{ "name": "walk", "from": 0, "to": 1 }}] }} }}"#
+            ),
+            format!(
+                r#"{{ "frames": [{frame}], "meta": {{ "image": "a.png",
+                    "frameTags": [{{ "name": "walk", "from": 0, "to": 0, "direction": "sideways" }}] }} }}"#
+            ),
+            r#"{ "frames": { "a": {} }, "meta": { "image": "a.png" } }"#.to_string(),
+            r#"{ "frames": [] }"#.to_string(),
+        ] {
+            assert!(
+                matches!(load(&json), Err(AssetError::Decode { .. })),
+                "{json}"
+            );
+        }
+    }
+}
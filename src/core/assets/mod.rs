@@ -1,7 +1,34 @@
+pub mod io;
+pub mod loaders;
+pub mod processing;
+
+mod asset_error;
+mod asset_event;
+mod asset_group;
+mod asset_loader;
+mod asset_meta;
 mod asset_server;
+mod asset_stats;
+mod asset_storage;
+#[allow(clippy::module_inception)]
 mod assets;
+mod handle_allocator;
+mod load_state;
+mod recursive_load_state;
 mod specifications;
 
-pub use asset_server::AssetServer;
+pub use asset_error::AssetError;
+pub use asset_event::AssetEvent;
+pub use asset_group::GroupProgress;
+pub use asset_loader::{AssetLoader, ErasedAssetLoader, LoadContext};
+pub use asset_meta::META_EXTENSION;
+pub use asset_server::{AssetServer, LoadedAsset};
+pub use asset_stats::AssetStats;
+pub use asset_storage::{AnyAssets, AssetStorage};
 pub use assets::Assets;
-pub use specifications::Image;
+pub use load_state::LoadState;
+pub use recursive_load_state::RecursiveLoadState;
+pub use specifications::{
+    AnimationClip, AnimationDirection, GridLayout, Image, ImageAddressMode, ImageFilter,
+    ImageSampler, ImageSettings, MipmapGeneration, TextureAtlas,
+};
@@ -0,0 +1,588 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::core::{
+    assets::{
+        asset_meta::meta_path,
+        io::{list_files, FileAssetReader},
+        AssetError, AssetLoader, AssetServer, Image, META_EXTENSION,
+    },
+    render::Rect,
+    resources::specifications::ATLAS_PAGE_SIZE,
+    utils::{HandleId, SkylinePacker},
+};
+
+use super::{ProcessCache, ProcessReport};
+
+const PROCESSOR_VERSION: u32 = 1;
+const DEFAULT_MANIFEST: &str = "groups.json";
+const CACHE_FILE: &str = ".corvus-cache";
+const ATLAS_DIRECTORY: &str = "atlases";
+const ATLAS_PADDING: u32 = 1;
+const FILES_GROUP: &str = "corvus-assets:files";
+const LABEL_SEPARATOR: char = '#';
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct SourceFile {
+    path: String,
+    bytes: Vec<u8>,
+    meta: Option<Vec<u8>>,
+    hash: String,
+}
+
+struct Validation {
+    files: Vec<SourceFile>,
+    images: HashMap<String, Image>,
+    groups: Vec<String>,
+    report: ProcessReport,
+}
+
+struct AtlasFrame {
+    name: String,
+    image: Image,
+}
+
+pub struct AssetProcessor {
+    source: PathBuf,
+    manifest: String,
+    asset_server: AssetServer,
+}
+
+impl AssetProcessor {
+    pub fn new(source: impl Into<PathBuf>) -> Self {
+        let source = source.into();
+
+        let mut asset_server = AssetServer::new();
+        asset_server.set_default_source(FileAssetReader::new(&source));
+
+        Self {
+            source,
+            manifest: DEFAULT_MANIFEST.to_string(),
+            asset_server,
+        }
+    }
+
+    pub fn with_manifest(mut self, manifest: &str) -> Self {
+        self.manifest = manifest.to_string();
+        self
+    }
+
+    pub fn register_loader<L: AssetLoader>(&mut self, loader: L) {
+        self.asset_server.register_loader(loader);
+    }
+
+    pub fn validate(&mut self) -> io::Result<ProcessReport> {
+        Ok(self.load_all(None)?.report)
+    }
+
+    pub fn process(&mut self, output: impl AsRef<Path>) -> io::Result<ProcessReport> {
+        let output = output.as_ref();
+        std::fs::create_dir_all(output)?;
+
+        let Validation {
+            files,
+            mut images,
+            groups,
+            mut report,
+        } = self.load_all(Some(output))?;
+
+        let cache_path = output.join(CACHE_FILE);
+        let mut cache = ProcessCache::load(&cache_path);
+        let mut current = HashSet::new();
+
+        let failed = report
+            .errors
+            .iter()
+            .filter(|err| !matches!(err, AssetError::MissingLabel { .. }))
+            .filter_map(error_path)
+            .map(str::to_string)
+            .collect::<HashSet<_>>();
+
+        for group in &groups {
+            let Some(atlas_path) =
+                self.pack_group_atlas(group, &files, &images, output, &mut cache, &mut report)?
+            else {
+                continue;
+            };
+
+            current.insert(atlas_path);
+        }
+
+        for file in &files {
+            if failed.contains(file.path.as_str()) {
+                continue;
+            }
+
+            current.insert(file.path.clone());
+
+            let output_path = output.join(&file.path);
+            if cache.get(&file.path) == Some(&file.hash) && output_path.exists() {
+                report.cached.push(file.path.clone());
+                continue;
+            }
+
+            let bytes = match images.remove(&file.path) {
+                Some(image)
+                    if image.settings.premultiplied_alpha || !image.mip_levels.is_empty() =>
+                {
+                    image.to_processed()
+                }
+                _ => file.bytes.clone(),
+            };
+
+            write_file(&output_path, &bytes)?;
+
+            let meta_output = output.join(meta_path(&file.path));
+            match &file.meta {
+                Some(meta) => write_file(&meta_output, meta)?,
+                None => remove_file(&meta_output)?,
+            }
+
+            cache.insert(&file.path, file.hash.clone());
+            report.processed.push(file.path.clone());
+        }
+
+        let stale = cache
+            .paths()
+            .filter(|path| !current.contains(*path))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        for path in stale {
+            remove_file(&output.join(&path))?;
+            remove_file(&output.join(meta_path(&path)))?;
+
+            if let Some(sheet_path) = atlas_sheet_path(&path) {
+                remove_file(&output.join(sheet_path))?;
+            }
+
+            cache.remove(&path);
+            report.removed.push(path);
+        }
+
+        cache.save(&cache_path)?;
+
+        Ok(report)
+    }
+
+    fn load_all(&mut self, exclude: Option<&Path>) -> io::Result<Validation> {
+        let exclude = exclude.and_then(|exclude| exclude.canonicalize().ok());
+        let mut report = ProcessReport::default();
+        let mut metas = HashMap::new();
+        let mut files = Vec::new();
+
+        for file in list_files(&self.source)? {
+            if exclude.as_ref().is_some_and(|exclude| {
+                file.canonicalize()
+                    .is_ok_and(|file| file.starts_with(exclude))
+            }) {
+                continue;
+            }
+
+            let Ok(relative_path) = file.strip_prefix(&self.source) else {
+                continue;
+            };
+
+            let path = relative_path.to_string_lossy().replace('\\', "/");
+            let bytes = std::fs::read(&file)?;
+
+            match path.strip_suffix(&format!(".{META_EXTENSION}")) {
+                Some(asset_path) => {
+                    metas.insert(asset_path.to_string(), bytes);
+                }
+                None => files.push((path, bytes)),
+            }
+        }
+
+        let file_paths = files
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect::<HashSet<_>>();
+
+        for asset_path in metas.keys() {
+            if !file_paths.contains(asset_path) {
+                report.unused.push(meta_path(asset_path));
+            }
+        }
+
+        let files = files
+            .into_iter()
+            .map(|(path, bytes)| {
+                let meta = metas.remove(&path);
+                let hash = source_hash(&bytes, meta.as_deref());
+
+                SourceFile {
+                    path,
+                    bytes,
+                    meta,
+                    hash,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let groups = match self.asset_server.load_group_manifest(&self.manifest) {
+            Ok(groups) => groups,
+            Err(err) => {
+                report.errors.push(err);
+                Vec::new()
+            }
+        };
+
+        let load_paths = files
+            .iter()
+            .map(|file| file.path.as_str())
+            .filter(|&path| path != self.manifest)
+            .collect::<Vec<_>>();
+        self.asset_server.load_group(FILES_GROUP, &load_paths);
+
+        let images = self.wait_for_loads(&groups)?;
+
+        let mut checked = HashSet::new();
+        let mut pending = self
+            .asset_server
+            .get_group(FILES_GROUP)
+            .into_iter()
+            .chain(
+                groups
+                    .iter()
+                    .filter_map(|group| self.asset_server.get_group(group)),
+            )
+            .flatten()
+            .collect::<VecDeque<_>>();
+
+        while let Some(handle_id) = pending.pop_front() {
+            if !checked.insert(handle_id) {
+                continue;
+            }
+
+            pending.extend(self.asset_server.get_dependencies(&handle_id));
+
+            match self.asset_server.get_load_error(&handle_id) {
+                None | Some(AssetError::MissingLoader { .. }) => {}
+                Some(err) => {
+                    let labeled = self
+                        .asset_server
+                        .get_path(handle_id)
+                        .is_some_and(|path| path.contains(LABEL_SEPARATOR));
+
+                    if !labeled || matches!(err, AssetError::MissingLabel { .. }) {
+                        report.errors.push(err.clone());
+                    }
+                }
+            }
+        }
+
+        if !groups.is_empty() {
+            let referenced = self.referenced_paths(&groups);
+
+            for file in &files {
+                if file.path != self.manifest && !referenced.contains(&file.path) {
+                    report.unused.push(file.path.clone());
+                }
+            }
+        }
+
+        report.unused.sort();
+
+        Ok(Validation {
+            files,
+            images,
+            groups,
+            report,
+        })
+    }
+
+    fn wait_for_loads(&mut self, groups: &[String]) -> io::Result<HashMap<String, Image>> {
+        let mut images = HashMap::new();
+        let mut last_progress = Instant::now();
+
+        loop {
+            self.asset_server.receive_loaded();
+
+            while let Some(loaded_asset) = self.asset_server.pop_loaded() {
+                last_progress = Instant::now();
+
+                let Some(path) = self.asset_server.get_path(loaded_asset.handle_id) else {
+                    continue;
+                };
+
+                if path.contains(LABEL_SEPARATOR) {
+                    continue;
+                }
+
+                if let Ok(image) = loaded_asset.asset.downcast::<Image>() {
+                    images.insert(path, *image);
+                }
+            }
+
+            let finished = std::iter::once(FILES_GROUP)
+                .chain(groups.iter().map(String::as_str))
+                .filter_map(|group| self.asset_server.group_progress(group))
+                .all(|progress| progress.is_finished());
+
+            if finished {
+                return Ok(images);
+            }
+
+            // A loader that panics takes its job with it, so the asset would never finish.
+            if last_progress.elapsed() > LOAD_TIMEOUT {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!(
+                        "no asset finished loading in {} seconds",
+                        LOAD_TIMEOUT.as_secs()
+                    ),
+                ));
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn referenced_paths(&self, groups: &[String]) -> HashSet<String> {
+        let mut referenced = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut pending = groups
+            .iter()
+            .filter_map(|group| self.asset_server.get_group(group))
+            .flatten()
+            .collect::<VecDeque<HandleId>>();
+
+        while let Some(handle_id) = pending.pop_front() {
+            if !visited.insert(handle_id) {
+                continue;
+            }
+
+            pending.extend(self.asset_server.get_dependencies(&handle_id));
+
+            let Some(path) = self.asset_server.get_path(handle_id) else {
+                continue;
+            };
+
+            match path.split_once(LABEL_SEPARATOR) {
+                Some((parent_path, _)) => {
+                    if let Some(parent_id) = self.asset_server.get_id_by_path(parent_path) {
+                        pending.push_back(parent_id);
+                    }
+                }
+                None => {
+                    referenced.insert(path);
+                }
+            }
+        }
+
+        referenced
+    }
+
+    fn pack_group_atlas(
+        &self,
+        group: &str,
+        files: &[SourceFile],
+        images: &HashMap<String, Image>,
+        output: &Path,
+        cache: &mut ProcessCache,
+        report: &mut ProcessReport,
+    ) -> io::Result<Option<String>> {
+        let mut members = self
+            .asset_server
+            .get_group(group)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|handle_id| self.asset_server.get_path(handle_id))
+            .map(|path| match path.split_once(LABEL_SEPARATOR) {
+                Some((parent_path, _)) => parent_path.to_string(),
+                None => path,
+            })
+            .filter_map(|path| files.iter().find(|file| file.path == path))
+            .collect::<Vec<_>>();
+        members.sort_by(|a, b| a.path.cmp(&b.path));
+        members.dedup_by(|a, b| a.path == b.path);
+
+        let mut frames = Vec::new();
+        for member in &members {
+            let Some(image) = images.get(&member.path).filter(|image| can_pack(image)) else {
+                continue;
+            };
+
+            match image.settings.atlas {
+                Some(grid) => {
+                    for (index, rect) in grid.rects().into_iter().enumerate() {
+                        let name = format!("{}#frame_{index}", member.path);
+
+                        if let Some(image) = image.crop(&name, rect) {
+                            frames.push(AtlasFrame { name, image });
+                        }
+                    }
+                }
+                None => {
+                    let (width, height) = image.dimensions;
+
+                    if let Some(image) = image.crop(&member.path, Rect::new(0, 0, width, height)) {
+                        frames.push(AtlasFrame {
+                            name: member.path.clone(),
+                            image,
+                        });
+                    }
+                }
+            }
+        }
+
+        if frames.is_empty() {
+            return Ok(None);
+        }
+
+        let atlas_path = format!("{ATLAS_DIRECTORY}/{group}.png");
+        let hash = source_hash(
+            group.as_bytes(),
+            Some(
+                members
+                    .iter()
+                    .map(|member| member.hash.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    .as_bytes(),
+            ),
+        );
+
+        if cache.get(&atlas_path) == Some(&hash) && output.join(&atlas_path).exists() {
+            report.cached.push(atlas_path.clone());
+            return Ok(Some(atlas_path));
+        }
+
+        frames.sort_by_key(|frame| std::cmp::Reverse(frame.image.dimensions.1));
+
+        let mut packer = SkylinePacker::new(ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE);
+        let mut placements = Vec::with_capacity(frames.len());
+        let mut size = (0, 0);
+
+        for frame in &frames {
+            let (width, height) = frame.image.dimensions;
+
+            let Some((x, y)) = packer.pack(width + ATLAS_PADDING, height + ATLAS_PADDING) else {
+                report.errors.push(AssetError::Decode {
+                    path: atlas_path,
+                    message: format!(
+                        "\"{}\" does not fit on a {ATLAS_PAGE_SIZE}x{ATLAS_PAGE_SIZE} atlas page",
+                        frame.name
+                    ),
+                });
+                return Ok(None);
+            };
+
+            size = (size.0.max(x + width), size.1.max(y + height));
+            placements.push(Rect::new(x, y, width, height));
+        }
+
+        let mut page = image::RgbaImage::new(size.0, size.1);
+        let mut sheet_frames = serde_json::Map::new();
+
+        for (frame, rect) in frames.iter().zip(&placements) {
+            image::imageops::replace(&mut page, &frame.image.data, rect.x as i64, rect.y as i64);
+
+            sheet_frames.insert(
+                frame.name.clone(),
+                serde_json::json!({
+                    "frame": { "x": rect.x, "y": rect.y, "w": rect.w, "h": rect.h },
+                }),
+            );
+        }
+
+        let mut png = Vec::new();
+        page.write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(io::Error::other)?;
+
+        let sheet = serde_json::json!({
+            "frames": sheet_frames,
+            "meta": {
+                "image": format!("{group}.png"),
+                "size": { "w": size.0, "h": size.1 },
+            },
+        });
+
+        write_file(&output.join(&atlas_path), &png)?;
+        write_file(
+            &output.join(atlas_sheet_path(&atlas_path).unwrap_or_default()),
+            serde_json::to_string_pretty(&sheet)?.as_bytes(),
+        )?;
+
+        cache.insert(&atlas_path, hash);
+        report.atlases.push(atlas_path.clone());
+
+        Ok(Some(atlas_path))
+    }
+}
+
+fn can_pack(image: &Image) -> bool {
+    let settings = &image.settings;
+
+    settings.srgb
+        && !settings.premultiplied_alpha
+        && !settings.mipmaps
+        && settings.sampler.is_none()
+        && image.dimensions.0 <= ATLAS_PAGE_SIZE
+        && image.dimensions.1 <= ATLAS_PAGE_SIZE
+}
+
+fn atlas_sheet_path(path: &str) -> Option<String> {
+    let name = path.strip_prefix(&format!("{ATLAS_DIRECTORY}/"))?;
+
+    Some(format!(
+        "{ATLAS_DIRECTORY}/{}.json",
+        name.strip_suffix(".png")?
+    ))
+}
+
+fn error_path(err: &AssetError) -> Option<&str> {
+    match err {
+        AssetError::NotFound(path)
+        | AssetError::UnsupportedFormat(path)
+        | AssetError::Corrupted(path)
+        | AssetError::UnknownSource { path, .. }
+        | AssetError::Io { path, .. }
+        | AssetError::MissingLoader { path, .. }
+        | AssetError::MissingLabel { path, .. }
+        | AssetError::Decode { path, .. } => Some(
+            path.strip_suffix(&format!(".{META_EXTENSION}"))
+                .unwrap_or(path),
+        ),
+    }
+}
+
+fn source_hash(bytes: &[u8], meta: Option<&[u8]>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(PROCESSOR_VERSION.to_le_bytes());
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+
+    if let Some(meta) = meta {
+        hasher.update(meta);
+    }
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, bytes)
+}
+
+fn remove_file(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
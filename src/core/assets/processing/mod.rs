@@ -0,0 +1,7 @@
+mod asset_processor;
+mod process_cache;
+mod process_report;
+
+pub use asset_processor::AssetProcessor;
+pub use process_cache::ProcessCache;
+pub use process_report::ProcessReport;
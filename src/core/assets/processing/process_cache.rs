@@ -0,0 +1,43 @@
+use std::{collections::BTreeMap, io, path::Path};
+
+#[derive(Debug, Default)]
+pub struct ProcessCache {
+    hashes: BTreeMap<String, String>,
+}
+
+impl ProcessCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let hashes = std::fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Self { hashes }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.hashes)?;
+
+        std::fs::write(path, json + "\n")
+    }
+
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.hashes.get(path).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, path: &str, hash: String) {
+        self.hashes.insert(path.to_string(), hash);
+    }
+
+    pub fn remove(&mut self, path: &str) -> bool {
+        self.hashes.remove(path).is_some()
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.hashes.keys().map(String::as_str)
+    }
+}
@@ -0,0 +1,17 @@
+use crate::core::assets::AssetError;
+
+#[derive(Debug, Default)]
+pub struct ProcessReport {
+    pub processed: Vec<String>,
+    pub cached: Vec<String>,
+    pub removed: Vec<String>,
+    pub atlases: Vec<String>,
+    pub unused: Vec<String>,
+    pub errors: Vec<AssetError>,
+}
+
+impl ProcessReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
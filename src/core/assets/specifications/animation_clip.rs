@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationDirection {
+    #[default]
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationClip {
+    pub frames: Vec<usize>,
+    pub frame_durations: Vec<Duration>,
+    pub direction: AnimationDirection,
+    pub repeat: Option<u32>,
+}
+
+impl AnimationClip {
+    pub fn duration(&self) -> Duration {
+        self.frame_durations.iter().sum()
+    }
+}
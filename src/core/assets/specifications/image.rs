@@ -1,20 +1,276 @@
+use std::io::{Read, Write};
+
+use flate2::{read::DeflateDecoder, write::DeflateEncoder};
+
+use crate::core::{assets::AssetError, render::Rect};
+
+use super::{ImageSettings, MipmapGeneration};
+
+const MISSING_TEXTURE_SIZE: u32 = 16;
+const MISSING_TEXTURE_CELL: u32 = 8;
+
+const PROCESSED_MAGIC: &[u8; 4] = b"CTEX";
+const PROCESSED_VERSION: u16 = 1;
+const PROCESSED_HEADER_SIZE: usize = 20;
+const PROCESSED_PREMULTIPLIED: u16 = 1;
+
+type ImageData = image::ImageBuffer<image::Rgba<u8>, Vec<u8>>;
+
 #[derive(Debug)]
 pub struct Image {
     pub path: String,
-    pub data: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+    pub data: ImageData,
     pub dimensions: (u32, u32),
+    pub mip_levels: Vec<ImageData>,
+    pub settings: ImageSettings,
 }
 
 impl Image {
-    pub fn new(path: &str) -> Self {
-        let image = image::open(path).unwrap();
+    pub fn from_bytes(path: &str, bytes: &[u8]) -> Result<Self, AssetError> {
+        if bytes.starts_with(PROCESSED_MAGIC) {
+            return Self::from_processed(path, bytes);
+        }
+
+        let format = image::ImageFormat::from_path(path)
+            .map_err(|_| AssetError::UnsupportedFormat(path.to_string()))?;
+
+        let image =
+            image::load_from_memory_with_format(bytes, format).map_err(|err| match err {
+                image::ImageError::Unsupported(_) => {
+                    AssetError::UnsupportedFormat(path.to_string())
+                }
+                _ => AssetError::Decode {
+                    path: path.to_string(),
+                    message: err.to_string(),
+                },
+            })?;
+
         let data = image.to_rgba8();
         let dimensions = data.dimensions();
 
-        Self {
+        Ok(Self {
             path: path.to_string(),
             data,
             dimensions,
+            mip_levels: Vec::new(),
+            settings: ImageSettings::default(),
+        })
+    }
+
+    pub fn from_processed(path: &str, bytes: &[u8]) -> Result<Self, AssetError> {
+        let corrupted = || AssetError::Corrupted(path.to_string());
+
+        if bytes.len() < PROCESSED_HEADER_SIZE || !bytes.starts_with(PROCESSED_MAGIC) {
+            return Err(corrupted());
+        }
+
+        let read_u16 = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+
+        let version = read_u16(4);
+        if version != PROCESSED_VERSION {
+            return Err(AssetError::Decode {
+                path: path.to_string(),
+                message: format!("unsupported processed image version {version}"),
+            });
+        }
+
+        let flags = read_u16(6);
+        let dimensions = (read_u32(8), read_u32(12));
+        let level_count = read_u32(16);
+
+        let max_level_count = u32::BITS - dimensions.0.max(dimensions.1).leading_zeros();
+        if dimensions.0 == 0 || dimensions.1 == 0 || level_count > max_level_count {
+            return Err(corrupted());
+        }
+
+        let size = (0..level_count)
+            .try_fold(0u64, |size, level| {
+                let width = (dimensions.0 >> level).max(1) as u64;
+                let height = (dimensions.1 >> level).max(1) as u64;
+
+                width.checked_mul(height)?.checked_mul(4)?.checked_add(size)
+            })
+            .ok_or_else(corrupted)?;
+
+        let mut data = Vec::new();
+        DeflateDecoder::new(&bytes[PROCESSED_HEADER_SIZE..])
+            .take(size.saturating_add(1))
+            .read_to_end(&mut data)
+            .map_err(|_| corrupted())?;
+
+        let mut levels = Vec::with_capacity(level_count as usize);
+        let mut remaining = data.as_slice();
+
+        for level in 0..level_count {
+            let width = (dimensions.0 >> level).max(1);
+            let height = (dimensions.1 >> level).max(1);
+            let size = width as usize * height as usize * 4;
+
+            if remaining.len() < size {
+                return Err(corrupted());
+            }
+
+            let (level_data, rest) = remaining.split_at(size);
+            remaining = rest;
+
+            let level =
+                ImageData::from_raw(width, height, level_data.to_vec()).ok_or_else(corrupted)?;
+            levels.push(level);
+        }
+
+        if levels.is_empty() || !remaining.is_empty() {
+            return Err(corrupted());
+        }
+
+        let data = levels.remove(0);
+
+        Ok(Self {
+            path: path.to_string(),
+            data,
+            dimensions,
+            mip_levels: levels,
+            settings: ImageSettings {
+                premultiplied_alpha: flags & PROCESSED_PREMULTIPLIED != 0,
+                ..Default::default()
+            },
+        })
+    }
+
+    pub fn to_processed(&self) -> Vec<u8> {
+        let flags = match self.settings.premultiplied_alpha {
+            true => PROCESSED_PREMULTIPLIED,
+            false => 0,
+        };
+
+        let mut bytes = Vec::with_capacity(PROCESSED_HEADER_SIZE);
+        bytes.extend_from_slice(PROCESSED_MAGIC);
+        bytes.extend_from_slice(&PROCESSED_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&flags.to_le_bytes());
+        bytes.extend_from_slice(&self.dimensions.0.to_le_bytes());
+        bytes.extend_from_slice(&self.dimensions.1.to_le_bytes());
+        bytes.extend_from_slice(&(self.mip_levels.len() as u32 + 1).to_le_bytes());
+
+        let mut encoder = DeflateEncoder::new(bytes, flate2::Compression::default());
+        for level in std::iter::once(&self.data).chain(&self.mip_levels) {
+            encoder
+                .write_all(level.as_raw())
+                .expect("writing to a Vec never fails");
+        }
+
+        encoder.finish().expect("writing to a Vec never fails")
+    }
+
+    pub fn generate_mipmaps(&mut self) {
+        self.mip_levels.clear();
+
+        let (mut width, mut height) = self.dimensions;
+        while width > 1 || height > 1 {
+            width = (width / 2).max(1);
+            height = (height / 2).max(1);
+
+            let source = self.mip_levels.last().unwrap_or(&self.data);
+            let level = image::imageops::resize(
+                source,
+                width,
+                height,
+                image::imageops::FilterType::Triangle,
+            );
+
+            self.mip_levels.push(level);
+        }
+    }
+
+    pub fn with_settings(mut self, settings: ImageSettings) -> Self {
+        if settings.premultiplied_alpha && !self.settings.premultiplied_alpha {
+            let levels = std::iter::once(&mut self.data).chain(&mut self.mip_levels);
+
+            for pixel in levels.flat_map(|level| level.pixels_mut()) {
+                let alpha = pixel[3] as u32;
+
+                for channel in &mut pixel.0[..3] {
+                    *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+                }
+            }
+        }
+
+        let generate_mipmaps =
+            settings.mipmaps && settings.mipmap_generation == MipmapGeneration::Cpu;
+        if generate_mipmaps && self.mip_levels.is_empty() {
+            self.generate_mipmaps();
+        }
+
+        self.settings = settings;
+        self
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        std::iter::once(&self.data)
+            .chain(&self.mip_levels)
+            .map(|level| level.as_raw().len())
+            .sum()
+    }
+
+    pub fn mip_level_count(&self) -> u32 {
+        if !self.settings.mipmaps {
+            return 1;
+        }
+
+        32 - self
+            .dimensions
+            .0
+            .max(self.dimensions.1)
+            .max(1)
+            .leading_zeros()
+    }
+
+    pub fn crop(&self, path: &str, rect: Rect) -> Option<Self> {
+        if rect.x + rect.w > self.dimensions.0 || rect.y + rect.h > self.dimensions.1 {
+            return None;
+        }
+
+        let data = image::imageops::crop_imm(&self.data, rect.x, rect.y, rect.w, rect.h).to_image();
+
+        let image = Self {
+            path: path.to_string(),
+            data,
+            dimensions: (rect.w, rect.h),
+            mip_levels: Vec::new(),
+            settings: ImageSettings {
+                premultiplied_alpha: self.settings.premultiplied_alpha,
+                ..Default::default()
+            },
+        };
+
+        Some(image.with_settings(ImageSettings {
+            atlas: None,
+            ..self.settings.clone()
+        }))
+    }
+
+    pub fn missing(path: &str) -> Self {
+        let data =
+            image::ImageBuffer::from_fn(MISSING_TEXTURE_SIZE, MISSING_TEXTURE_SIZE, |x, y| {
+                if ((x / MISSING_TEXTURE_CELL) ^ (y / MISSING_TEXTURE_CELL)) & 1 == 0 {
+                    image::Rgba([255, 0, 255, 255])
+                } else {
+                    image::Rgba([0, 0, 0, 255])
+                }
+            });
+
+        Self {
+            path: path.to_string(),
+            data,
+            dimensions: (MISSING_TEXTURE_SIZE, MISSING_TEXTURE_SIZE),
+            mip_levels: Vec::new(),
+            settings: ImageSettings::default(),
         }
     }
 }
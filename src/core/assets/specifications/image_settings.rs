@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use super::GridLayout;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageFilter {
+    #[default]
+    Nearest,
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageAddressMode {
+    #[default]
+    ClampToEdge,
+    Repeat,
+    MirrorRepeat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MipmapGeneration {
+    #[default]
+    Cpu,
+    Gpu,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageSampler {
+    pub mag_filter: ImageFilter,
+    pub min_filter: ImageFilter,
+    pub address_mode_u: ImageAddressMode,
+    pub address_mode_v: ImageAddressMode,
+    pub anisotropy: u16,
+}
+
+impl ImageSampler {
+    pub fn nearest() -> Self {
+        Self {
+            mag_filter: ImageFilter::Nearest,
+            min_filter: ImageFilter::Nearest,
+            address_mode_u: ImageAddressMode::ClampToEdge,
+            address_mode_v: ImageAddressMode::ClampToEdge,
+            anisotropy: 1,
+        }
+    }
+
+    pub fn linear() -> Self {
+        Self {
+            mag_filter: ImageFilter::Linear,
+            min_filter: ImageFilter::Linear,
+            ..Self::nearest()
+        }
+    }
+
+    pub fn with_address_mode(mut self, address_mode: ImageAddressMode) -> Self {
+        self.address_mode_u = address_mode;
+        self.address_mode_v = address_mode;
+        self
+    }
+
+    pub fn with_anisotropy(mut self, anisotropy: u16) -> Self {
+        self.anisotropy = anisotropy;
+        self
+    }
+}
+
+impl Default for ImageSampler {
+    fn default() -> Self {
+        Self::nearest()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageSettings {
+    pub sampler: Option<ImageSampler>,
+    pub srgb: bool,
+    pub premultiplied_alpha: bool,
+    pub mipmaps: bool,
+    pub mipmap_generation: MipmapGeneration,
+    pub atlas: Option<GridLayout>,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            sampler: None,
+            srgb: true,
+            premultiplied_alpha: false,
+            mipmaps: false,
+            mipmap_generation: MipmapGeneration::default(),
+            atlas: None,
+        }
+    }
+}
@@ -1,3 +1,11 @@
+mod animation_clip;
 mod image;
+mod image_settings;
+mod texture_atlas;
 
+pub use animation_clip::{AnimationClip, AnimationDirection};
 pub use image::Image;
+pub use image_settings::{
+    ImageAddressMode, ImageFilter, ImageSampler, ImageSettings, MipmapGeneration,
+};
+pub use texture_atlas::{GridLayout, TextureAtlas};
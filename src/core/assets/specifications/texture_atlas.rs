@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{assets::Image, render::Rect, utils::Handle};
+
+use super::AnimationClip;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridLayout {
+    pub tile_size: (u32, u32),
+    pub columns: u32,
+    pub rows: u32,
+    #[serde(default)]
+    pub padding: (u32, u32),
+    #[serde(default)]
+    pub offset: (u32, u32),
+}
+
+impl GridLayout {
+    pub fn new(tile_size: (u32, u32), columns: u32, rows: u32) -> Self {
+        Self {
+            tile_size,
+            columns,
+            rows,
+            padding: (0, 0),
+            offset: (0, 0),
+        }
+    }
+
+    pub fn with_padding(mut self, padding: (u32, u32)) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: (u32, u32)) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn rects(&self) -> Vec<Rect> {
+        let (tile_width, tile_height) = self.tile_size;
+
+        (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                Rect::new(
+                    self.offset.0 + column * (tile_width + self.padding.0),
+                    self.offset.1 + row * (tile_height + self.padding.1),
+                    tile_width,
+                    tile_height,
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct TextureAtlas {
+    pub image: Handle<Image>,
+    pub frames: Vec<Rect>,
+    pub frame_names: HashMap<String, usize>,
+    pub clips: HashMap<String, AnimationClip>,
+}
+
+impl TextureAtlas {
+    pub fn from_grid(image: Handle<Image>, grid: GridLayout) -> Self {
+        Self::from_rects(image, grid.rects())
+    }
+
+    pub fn from_rects(image: Handle<Image>, frames: Vec<Rect>) -> Self {
+        Self {
+            image,
+            frames,
+            frame_names: HashMap::new(),
+            clips: HashMap::new(),
+        }
+    }
+
+    pub fn frame(&self, index: usize) -> Option<Rect> {
+        self.frames.get(index).copied()
+    }
+
+    pub fn frame_index(&self, name: &str) -> Option<usize> {
+        self.frame_names.get(name).copied()
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
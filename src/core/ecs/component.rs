@@ -23,18 +23,14 @@ impl<T: Component> ComponentVec<T> {
         self.components[index] = None;
     }
 
-    pub fn get(&self, index: usize) -> Option<Ref<T>> {
-        let Some(ref component) = self.components[index] else {
-            return None;
-        };
+    pub fn get(&self, index: usize) -> Option<Ref<'_, T>> {
+        let component = self.components[index].as_ref()?;
 
         Some(Ref::map(component.borrow(), |component| component))
     }
 
-    pub fn get_mut(&self, index: usize) -> Option<RefMut<T>> {
-        let Some(ref component) = self.components[index] else {
-            return None;
-        };
+    pub fn get_mut(&self, index: usize) -> Option<RefMut<'_, T>> {
+        let component = self.components[index].as_ref()?;
 
         Some(RefMut::map(component.borrow_mut(), |component| component))
     }
@@ -0,0 +1,37 @@
+use crate::core::{
+    assets::{ImageSampler, TextureAtlas},
+    utils::Handle,
+};
+
+pub struct AtlasSprite {
+    pub atlas_handle: Handle<TextureAtlas>,
+    pub index: usize,
+    pub tint: [f32; 4],
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+    pub sampler: Option<ImageSampler>,
+}
+
+impl AtlasSprite {
+    pub fn new(
+        atlas_handle: Handle<TextureAtlas>,
+        index: usize,
+        tint: [f32; 4],
+        flip_horizontal: bool,
+        flip_vertical: bool,
+    ) -> Self {
+        Self {
+            atlas_handle,
+            index,
+            tint,
+            flip_horizontal,
+            flip_vertical,
+            sampler: None,
+        }
+    }
+
+    pub fn with_sampler(mut self, sampler: ImageSampler) -> Self {
+        self.sampler = Some(sampler);
+        self
+    }
+}
@@ -1,7 +1,9 @@
+mod atlas_sprite;
 mod ortho_camera;
 mod sprite;
 mod transform;
 
-pub use ortho_camera::OrthoCamera;
+pub use atlas_sprite::AtlasSprite;
+pub use ortho_camera::{OrthoCamera, ScalingMode};
 pub use sprite::Sprite;
 pub use transform::Transform;
@@ -1,7 +1,19 @@
+use crate::core::render::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScalingMode {
+    Expand,
+    FixedVirtual { width: u32, height: u32 },
+    PixelPerfect { width: u32, height: u32 },
+    FitWidth(f32),
+    FitHeight(f32),
+}
+
 pub struct OrthoCamera {
     pub position: glam::Vec2,
     pub viewport: winit::dpi::PhysicalSize<u32>,
     pub zoom: f32,
+    scaling_mode: ScalingMode,
 }
 
 impl OrthoCamera {
@@ -10,17 +22,162 @@ impl OrthoCamera {
             position,
             viewport,
             zoom,
+            scaling_mode: ScalingMode::Expand,
+        }
+    }
+
+    pub fn with_scaling_mode(mut self, scaling_mode: ScalingMode) -> Self {
+        self.set_scaling_mode(scaling_mode);
+        self
+    }
+
+    pub fn scaling_mode(&self) -> ScalingMode {
+        self.scaling_mode
+    }
+
+    pub fn set_scaling_mode(&mut self, scaling_mode: ScalingMode) {
+        let valid = match scaling_mode {
+            ScalingMode::Expand => true,
+            ScalingMode::FixedVirtual { width, height }
+            | ScalingMode::PixelPerfect { width, height } => width > 0 && height > 0,
+            ScalingMode::FitWidth(size) | ScalingMode::FitHeight(size) => size > 0.0,
+        };
+
+        if !valid {
+            panic!("Camera scaling mode {scaling_mode:?} needs a non-zero virtual size");
         }
+
+        self.scaling_mode = scaling_mode;
+    }
+
+    // Screen pixels per virtual pixel: an integer upscale when the virtual resolution fits the
+    // window, otherwise an integer downscale so pixels still map evenly onto the screen.
+    fn pixel_perfect_scale(&self, width: u32, height: u32) -> f32 {
+        let (width, height) = (width.max(1), height.max(1));
+        let viewport_width = self.viewport.width.max(1);
+        let viewport_height = self.viewport.height.max(1);
+
+        if viewport_width >= width && viewport_height >= height {
+            return (viewport_width / width).min(viewport_height / height) as f32;
+        }
+
+        let divisor = width
+            .div_ceil(viewport_width)
+            .max(height.div_ceil(viewport_height));
+
+        1.0 / divisor as f32
+    }
+
+    pub fn get_visible_size(&self) -> glam::Vec2 {
+        let viewport = glam::vec2(
+            self.viewport.width.max(1) as f32,
+            self.viewport.height.max(1) as f32,
+        );
+
+        let size = match self.scaling_mode {
+            ScalingMode::Expand => viewport,
+            ScalingMode::FixedVirtual { width, height } => glam::vec2(width as f32, height as f32),
+            ScalingMode::PixelPerfect { width, height } => {
+                let rect = self.get_viewport_rect();
+
+                glam::vec2(rect.w.max(1) as f32, rect.h.max(1) as f32)
+                    / self.pixel_perfect_scale(width, height)
+            }
+            ScalingMode::FitWidth(width) => glam::vec2(width, width * viewport.y / viewport.x),
+            ScalingMode::FitHeight(height) => glam::vec2(height * viewport.x / viewport.y, height),
+        };
+
+        size / self.zoom
+    }
+
+    pub fn get_viewport_rect(&self) -> Rect {
+        let (width, height) = match self.scaling_mode {
+            ScalingMode::FixedVirtual { width, height } => {
+                let scale = (self.viewport.width as f32 / width.max(1) as f32)
+                    .min(self.viewport.height as f32 / height.max(1) as f32);
+
+                (
+                    (width as f32 * scale) as u32,
+                    (height as f32 * scale) as u32,
+                )
+            }
+            ScalingMode::PixelPerfect { width, height } => {
+                let scale = self.pixel_perfect_scale(width, height);
+
+                (
+                    ((width as f32 * scale) as u32).min(self.viewport.width),
+                    ((height as f32 * scale) as u32).min(self.viewport.height),
+                )
+            }
+            _ => (self.viewport.width, self.viewport.height),
+        };
+
+        Rect::new(
+            self.viewport.width.saturating_sub(width) / 2,
+            self.viewport.height.saturating_sub(height) / 2,
+            width,
+            height,
+        )
     }
 
     pub fn get_view_projection(&self) -> glam::Mat4 {
-        glam::Mat4::orthographic_rh(
-            0.0,
-            self.viewport.width as f32 / self.zoom,
-            0.0,
-            self.viewport.height as f32 / self.zoom,
-            -10.0,
-            10.0,
-        ) * glam::Mat4::from_translation(self.position.extend(0.0))
+        let visible_size = self.get_visible_size();
+
+        glam::Mat4::orthographic_rh(0.0, visible_size.x, 0.0, visible_size.y, -10.0, 10.0)
+            * glam::Mat4::from_translation(self.position.extend(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::dpi::PhysicalSize;
+
+    use super::*;
+
+    fn pixel_perfect_camera(width: u32, height: u32) -> OrthoCamera {
+        OrthoCamera::new(glam::Vec2::ZERO, PhysicalSize::new(width, height), 1.0).with_scaling_mode(
+            ScalingMode::PixelPerfect {
+                width: 320,
+                height: 180,
+            },
+        )
+    }
+
+    #[test]
+    fn pixel_perfect_upscales_by_an_integer_and_letterboxes() {
+        let camera = pixel_perfect_camera(1000, 600);
+
+        assert_eq!(camera.get_viewport_rect(), Rect::new(20, 30, 960, 540));
+        assert_eq!(camera.get_visible_size(), glam::vec2(320.0, 180.0));
+    }
+
+    #[test]
+    fn pixel_perfect_downscales_by_an_integer_in_small_windows() {
+        let camera = pixel_perfect_camera(200, 150);
+        let rect = camera.get_viewport_rect();
+        let visible = camera.get_visible_size();
+
+        assert_eq!(rect, Rect::new(20, 30, 160, 90));
+        assert_eq!(
+            visible / glam::vec2(rect.w as f32, rect.h as f32),
+            glam::Vec2::splat(2.0)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_virtual_size_is_rejected() {
+        let _ = OrthoCamera::new(glam::Vec2::ZERO, PhysicalSize::new(800, 600), 1.0)
+            .with_scaling_mode(ScalingMode::FixedVirtual {
+                width: 0,
+                height: 180,
+            });
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_virtual_size_is_rejected_when_set_later() {
+        let mut camera = pixel_perfect_camera(800, 600);
+        camera.set_scaling_mode(ScalingMode::FitWidth(0.0));
     }
 }
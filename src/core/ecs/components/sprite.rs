@@ -1,4 +1,8 @@
-use crate::core::{assets::Image, render::Rect, utils::Handle};
+use crate::core::{
+    assets::{Image, ImageSampler},
+    render::Rect,
+    utils::Handle,
+};
 
 pub struct Sprite {
     pub texture_handle: Handle<Image>,
@@ -6,6 +10,7 @@ pub struct Sprite {
     pub tint: [f32; 4],
     pub flip_horizontal: bool,
     pub flip_vertical: bool,
+    pub sampler: Option<ImageSampler>,
 }
 
 impl Sprite {
@@ -22,6 +27,12 @@ impl Sprite {
             tint,
             flip_horizontal,
             flip_vertical,
+            sampler: None,
         }
     }
+
+    pub fn with_sampler(mut self, sampler: ImageSampler) -> Self {
+        self.sampler = Some(sampler);
+        self
+    }
 }
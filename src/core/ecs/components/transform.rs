@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 pub struct Transform {
     pub position: glam::Vec3,
     pub scale: glam::Vec2,
@@ -15,3 +17,12 @@ impl Transform {
         }
     }
 }
+
+impl Hash for Transform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position.to_array().map(f32::to_bits).hash(state);
+        self.scale.to_array().map(f32::to_bits).hash(state);
+        self.rotation.to_bits().hash(state);
+        self.origin.to_array().map(f32::to_bits).hash(state);
+    }
+}
@@ -1,26 +1,316 @@
 use crate::core::{
-    assets::{AssetServer, Assets, Image},
-    render::graphics,
-    resources::{specifications::GpuImage, Resources},
+    assets::{AssetServer, AssetStorage, Assets, Image},
+    render::{graphics, MipmapGenerator},
+    resources::{
+        specifications::{AtlasPlacement, DynamicAtlas, GpuImage, ATLAS_PAGE_SIZE},
+        Resources,
+    },
+    utils::HandleId,
 };
 
-pub fn load_pending_assets(
+const UPLOAD_BUDGET_BYTES: usize = 16 * 1024 * 1024;
+
+pub fn upload_loaded_assets(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
     asset_server: &mut AssetServer,
-    assets: &mut Assets,
+    assets: &mut AssetStorage,
     resources: &mut Resources,
 ) {
-    for pending_path in asset_server.get_pending_to_load() {
-        if let Some(handle_id) = asset_server.get_id_by_path(&pending_path) {
-            let image = Image::new(&pending_path);
+    asset_server.check_for_changes();
+    asset_server.receive_loaded();
+
+    let mut uploaded_bytes = 0;
+    while uploaded_bytes < UPLOAD_BUDGET_BYTES {
+        let Some(loaded_asset) = asset_server.pop_loaded() else {
+            break;
+        };
+
+        uploaded_bytes += loaded_asset.size;
 
-            let (texture, view) =
-                graphics::create_texture(device, queue, &image.data, image.dimensions);
-            let gpu_image = GpuImage::new(texture, view);
-            resources.textures.insert(handle_id.clone(), gpu_image);
+        let handle_id = loaded_asset.handle_id;
+        let is_image = loaded_asset.asset.is::<Image>();
 
-            assets.images.insert(handle_id.clone(), image);
+        if !assets.insert_boxed(handle_id, loaded_asset.asset) {
+            log::error!(
+                "Loaded asset \"{}\" has a type that was not registered",
+                asset_server.get_path(handle_id).unwrap_or_default()
+            );
+            continue;
         }
+
+        if is_image {
+            upload_image(
+                device,
+                queue,
+                asset_server,
+                assets.get::<Image>(),
+                resources,
+                handle_id,
+            );
+        }
+    }
+
+    asset_server.update_groups();
+}
+
+pub fn unload_unused_assets(
+    asset_server: &mut AssetServer,
+    assets: &mut AssetStorage,
+    resources: &mut Resources,
+) {
+    for handle_id in asset_server.collect_unused() {
+        assets.remove(&handle_id);
+        resources.remove_texture(&handle_id);
+        resources.atlas.remove(&handle_id);
     }
 }
+
+pub fn insert_missing_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    asset_server: &AssetServer,
+    images: &mut Assets<Image>,
+    resources: &mut Resources,
+) {
+    let handle_id = asset_server.missing_texture().id();
+    let image = Image::missing(&asset_server.get_path(handle_id).unwrap_or_default());
+
+    let gpu_image = create_gpu_image(device, queue, &image, resources);
+    resources.insert_texture(handle_id, gpu_image);
+    images.insert(handle_id, image);
+}
+
+pub fn upload_assets(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    asset_server: &mut AssetServer,
+    images: &Assets<Image>,
+    resources: &mut Resources,
+) {
+    let mut handle_ids = images
+        .iter()
+        .map(|(handle_id, _)| *handle_id)
+        .collect::<Vec<_>>();
+    handle_ids.sort_by_key(|handle_id| {
+        std::cmp::Reverse(images.get(handle_id).map_or(0, |image| image.dimensions.1))
+    });
+
+    for handle_id in handle_ids {
+        upload_image(device, queue, asset_server, images, resources, handle_id);
+    }
+}
+
+fn upload_image(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    asset_server: &mut AssetServer,
+    images: &Assets<Image>,
+    resources: &mut Resources,
+    handle_id: HandleId,
+) {
+    let Some(image) = images.get(&handle_id) else {
+        return;
+    };
+
+    resources.atlas.remove(&handle_id);
+
+    if handle_id == asset_server.missing_texture().id() || !can_pack(image) {
+        let gpu_image = create_gpu_image(device, queue, image, resources);
+        resources.insert_texture(handle_id, gpu_image);
+        return;
+    }
+
+    resources.remove_texture(&handle_id);
+
+    let placement = match resources.atlas.allocate(handle_id, image.dimensions) {
+        Some(placement) => placement,
+        None => {
+            if resources.atlas.wasted_area() > 0 {
+                // Repacking in a different order can leave images out that fit incrementally.
+                for (unplaced_id, unplaced) in repack_atlas(queue, images, resources) {
+                    let placement = allocate_in_atlas(
+                        device,
+                        queue,
+                        asset_server,
+                        resources,
+                        unplaced_id,
+                        unplaced.dimensions,
+                    );
+                    write_to_atlas(queue, resources, unplaced, placement);
+                }
+            }
+
+            let placement = allocate_in_atlas(
+                device,
+                queue,
+                asset_server,
+                resources,
+                handle_id,
+                image.dimensions,
+            );
+
+            for page in resources.atlas.remove_empty_pages() {
+                log::debug!("Removing empty texture atlas page");
+                resources.remove_texture(&page.id());
+            }
+
+            placement
+        }
+    };
+
+    write_to_atlas(queue, resources, image, placement);
+}
+
+fn allocate_in_atlas(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    asset_server: &mut AssetServer,
+    resources: &mut Resources,
+    handle_id: HandleId,
+    dimensions: (u32, u32),
+) -> AtlasPlacement {
+    if let Some(placement) = resources.atlas.allocate(handle_id, dimensions) {
+        return placement;
+    }
+
+    add_atlas_page(device, queue, asset_server, resources);
+
+    resources
+        .atlas
+        .allocate(handle_id, dimensions)
+        .expect("a packable image always fits on an empty atlas page")
+}
+
+fn repack_atlas<'a>(
+    queue: &wgpu::Queue,
+    images: &'a Assets<Image>,
+    resources: &mut Resources,
+) -> Vec<(HandleId, &'a Image)> {
+    let mut packed = resources
+        .atlas
+        .clear()
+        .into_iter()
+        .filter_map(|handle_id| Some((handle_id, images.get(&handle_id)?.as_ref())))
+        .collect::<Vec<_>>();
+    packed.sort_by_key(|(_, image)| std::cmp::Reverse(image.dimensions.1));
+
+    log::debug!("Repacking {} images into the texture atlas", packed.len());
+
+    // Padding is only on the right and bottom, so old texels would bleed into new neighbours.
+    let zeroed = vec![0; (ATLAS_PAGE_SIZE * ATLAS_PAGE_SIZE * 4) as usize];
+    for page_id in resources.atlas.page_ids() {
+        if let Some(page) = resources.textures.get(&page_id) {
+            graphics::write_texture_region(
+                queue,
+                &page.texture,
+                &zeroed,
+                (0, 0),
+                (ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE),
+            );
+        }
+    }
+
+    let mut unplaced = Vec::new();
+    for (handle_id, image) in packed {
+        match resources.atlas.allocate(handle_id, image.dimensions) {
+            Some(placement) => write_to_atlas(queue, resources, image, placement),
+            None => unplaced.push((handle_id, image)),
+        }
+    }
+
+    unplaced
+}
+
+fn add_atlas_page(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    asset_server: &mut AssetServer,
+    resources: &mut Resources,
+) {
+    let page = asset_server.reserve::<Image>();
+    let data = vec![0; (ATLAS_PAGE_SIZE * ATLAS_PAGE_SIZE * 4) as usize];
+    let (texture, view) = graphics::create_texture(
+        device,
+        queue,
+        &[&data],
+        (ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE),
+        wgpu::TextureFormat::Rgba8UnormSrgb,
+        1,
+    );
+
+    log::debug!(
+        "Adding texture atlas page {}",
+        resources.atlas.page_count() + 1
+    );
+
+    resources.insert_texture(page.id(), GpuImage::new(texture, view));
+    resources.atlas.add_page(page);
+}
+
+fn write_to_atlas(
+    queue: &wgpu::Queue,
+    resources: &Resources,
+    image: &Image,
+    placement: AtlasPlacement,
+) {
+    let Some(page) = resources.textures.get(&placement.page) else {
+        return;
+    };
+
+    graphics::write_texture_region(
+        queue,
+        &page.texture,
+        &image.data,
+        (placement.rect.x, placement.rect.y),
+        image.dimensions,
+    );
+}
+
+fn can_pack(image: &Image) -> bool {
+    let settings = &image.settings;
+
+    DynamicAtlas::can_pack(image.dimensions)
+        && settings.srgb
+        && !settings.premultiplied_alpha
+        && !settings.mipmaps
+        && settings.sampler.is_none()
+}
+
+fn create_gpu_image(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    image: &Image,
+    resources: &mut Resources,
+) -> GpuImage {
+    let format = match image.settings.srgb {
+        true => wgpu::TextureFormat::Rgba8UnormSrgb,
+        false => wgpu::TextureFormat::Rgba8Unorm,
+    };
+
+    let levels = std::iter::once(&image.data)
+        .chain(&image.mip_levels)
+        .map(|level| level.as_raw().as_slice())
+        .collect::<Vec<_>>();
+    let mip_level_count = image.mip_level_count();
+
+    let (texture, view) = graphics::create_texture(
+        device,
+        queue,
+        &levels,
+        image.dimensions,
+        format,
+        mip_level_count,
+    );
+
+    if levels.len() < mip_level_count as usize {
+        resources
+            .mipmap_generator
+            .get_or_insert_with(|| MipmapGenerator::new(device))
+            .generate(device, queue, &texture, levels.len() as u32);
+    }
+
+    GpuImage::new(texture, view)
+        .with_premultiplied_alpha(image.settings.premultiplied_alpha)
+        .with_sampler(image.settings.sampler)
+}
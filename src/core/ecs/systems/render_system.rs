@@ -1,26 +1,89 @@
 use crate::core::{
-    assets::Assets,
+    assets::{AssetServer, Assets, Image, ImageSampler, LoadState, TextureAtlas},
     ecs::{
-        components::{OrthoCamera, Sprite, Transform},
+        components::{AtlasSprite, OrthoCamera, Sprite, Transform},
         World,
     },
-    render::{SpriteInstance, SpriteRenderer},
+    render::{Rect, SpriteBatch, SpriteInstance, SpriteRenderer},
+    resources::specifications::{DynamicAtlas, ATLAS_PAGE_SIZE},
+    utils::HandleId,
 };
 
+struct SpriteDraw {
+    texture_id: HandleId,
+    source_rect: Rect,
+    tint: [f32; 4],
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    sampler: Option<ImageSampler>,
+}
+
+impl SpriteDraw {
+    fn from_sprite(sprite: &Sprite) -> Self {
+        Self {
+            texture_id: sprite.texture_handle.id(),
+            source_rect: sprite.source_rect,
+            tint: sprite.tint,
+            flip_horizontal: sprite.flip_horizontal,
+            flip_vertical: sprite.flip_vertical,
+            sampler: sprite.sampler,
+        }
+    }
+
+    fn from_atlas_sprite(
+        atlas_sprite: &AtlasSprite,
+        atlases: &Assets<TextureAtlas>,
+    ) -> Option<Self> {
+        let atlas = atlases.get(&atlas_sprite.atlas_handle.id())?;
+
+        Some(Self {
+            texture_id: atlas.image.id(),
+            source_rect: atlas.frame(atlas_sprite.index)?,
+            tint: atlas_sprite.tint,
+            flip_horizontal: atlas_sprite.flip_horizontal,
+            flip_vertical: atlas_sprite.flip_vertical,
+            sampler: atlas_sprite.sampler,
+        })
+    }
+}
+
+pub fn resize_cameras(world: &World, size: winit::dpi::PhysicalSize<u32>) {
+    for entity in world.entities() {
+        if let Some(mut ortho_camera) = world.get_component_mut::<OrthoCamera>(entity) {
+            ortho_camera.viewport = size;
+        }
+    }
+}
+
 pub fn set_camera_projection(world: &World, sprite_renderer: &mut SpriteRenderer) {
     let ortho_camera = world
         .single::<OrthoCamera>()
         .expect("there needs to be a main camera");
 
     sprite_renderer.update_view_projection(ortho_camera.get_view_projection());
+    sprite_renderer.update_viewport(ortho_camera.get_viewport_rect());
 }
 
-pub fn draw_sprites(world: &World, assets: &Assets, sprite_renderer: &mut SpriteRenderer) {
+pub fn draw_sprites(
+    world: &World,
+    asset_server: &AssetServer,
+    images: &Assets<Image>,
+    atlases: &Assets<TextureAtlas>,
+    dynamic_atlas: &DynamicAtlas,
+    sprite_batch: &mut SpriteBatch,
+) {
     let mut sprites = world
         .entities()
         .filter_map(|entity| {
             let transform = world.get_component::<Transform>(entity)?;
-            let sprite = world.get_component::<Sprite>(entity)?;
+
+            let sprite = match world.get_component::<Sprite>(entity) {
+                Some(sprite) => SpriteDraw::from_sprite(&sprite),
+                None => SpriteDraw::from_atlas_sprite(
+                    &*world.get_component::<AtlasSprite>(entity)?,
+                    atlases,
+                )?,
+            };
 
             Some((transform, sprite))
         })
@@ -41,66 +104,129 @@ pub fn draw_sprites(world: &World, assets: &Assets, sprite_renderer: &mut Sprite
     });
 
     for (transform, sprite) in sprites {
-        let Some(image) = assets.images.get(&sprite.texture_handle.id()) else {
+        let (texture_id, uv_rect) = match asset_server.get_load_state(&sprite.texture_id) {
+            LoadState::Failed => (asset_server.missing_texture().id(), None),
+            _ => (sprite.texture_id, Some(sprite.source_rect)),
+        };
+
+        let Some(image) = images.get(&texture_id) else {
             continue;
         };
 
-        let position = [[-1.0, 1.0], [-1.0, -1.0], [1.0, -1.0], [1.0, 1.0]]
-            .iter()
-            .map(|&[x, y]| {
-                let sized = glam::vec2(
-                    x * sprite.source_rect.w as f32,
-                    y * sprite.source_rect.h as f32,
-                );
-                let scaled = sized * transform.scale;
-                let originated = scaled
-                    + glam::vec2(
-                        transform.origin.x * sprite.source_rect.w as f32,
-                        transform.origin.y * sprite.source_rect.h as f32,
-                    );
-                let rotated = glam::Mat2::from_angle(transform.rotation.to_radians()) * originated;
-                let translated = rotated + transform.position.truncate();
-
-                [translated.x, translated.y]
-            })
-            .collect::<Vec<_>>();
+        let transform = {
+            let size = glam::vec2(sprite.source_rect.w as f32, sprite.source_rect.h as f32);
+            let rotation = glam::Mat2::from_angle(transform.rotation.to_radians());
 
-        let uv_coords = {
-            let rect = &sprite.source_rect;
+            glam::Affine2::from_mat2_translation(
+                rotation * glam::Mat2::from_diagonal(size * transform.scale),
+                rotation * (transform.origin * size) + transform.position.truncate(),
+            )
+        };
+
+        let (texture_id, (width, height), rect) = {
             let (width, height) = image.dimensions;
+            let rect = uv_rect.unwrap_or(Rect::new(0, 0, width, height));
+
+            match dynamic_atlas.get(&texture_id) {
+                Some(placement) => (
+                    placement.page,
+                    (ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE),
+                    Rect::new(
+                        placement.rect.x + rect.x,
+                        placement.rect.y + rect.y,
+                        rect.w,
+                        rect.h,
+                    ),
+                ),
+                None => (texture_id, image.dimensions, rect),
+            }
+        };
 
-            let u_min = rect.x as f32 / width as f32;
-            let v_min = rect.y as f32 / height as f32;
-            let u_max = (rect.x + rect.w) as f32 / width as f32;
-            let v_max = (rect.y + rect.h) as f32 / height as f32;
+        let uv_rect = [
+            rect.x as f32 / width as f32,
+            rect.y as f32 / height as f32,
+            (rect.x + rect.w) as f32 / width as f32,
+            (rect.y + rect.h) as f32 / height as f32,
+        ];
 
-            let mut uv = (u_min, v_min, u_max, v_max);
+        let sprite_instance = SpriteInstance {
+            texture_id,
+            transform,
+            uv_rect,
+            color: sprite.tint,
+            flip_horizontal: sprite.flip_horizontal,
+            flip_vertical: sprite.flip_vertical,
+            sampler: sprite.sampler,
+        };
 
-            if sprite.flip_horizontal {
-                uv = (uv.2, uv.1, uv.0, uv.3);
-            }
+        sprite_batch.draw(sprite_instance);
+    }
+}
 
-            if sprite.flip_vertical {
-                uv = (uv.0, uv.3, uv.2, uv.1);
-            }
+#[cfg(test)]
+mod tests {
+    use crate::core::render::InstanceData;
 
-            let (u_min, v_min, u_max, v_max) = uv;
+    use super::*;
 
-            vec![
-                [u_min, v_min],
-                [u_min, v_max],
-                [u_max, v_max],
-                [u_max, v_min],
-            ]
-        };
+    const SPRITE_TINT: [f32; 4] = [1.0, 0.5, 0.25, 1.0];
+    const ATLAS_SPRITE_TINT: [f32; 4] = [0.2, 0.4, 0.6, 0.8];
 
-        let sprite_instance = SpriteInstance {
-            handle_image: sprite.texture_handle.clone(),
-            color: [1.0, 1.0, 1.0, 1.0],
-            position,
-            uv_coords,
-        };
+    fn transform(z: f32) -> Transform {
+        Transform::new(
+            glam::vec3(0.0, 0.0, z),
+            glam::Vec2::ONE,
+            0.0,
+            glam::Vec2::ZERO,
+        )
+    }
+
+    #[test]
+    fn tints_become_instance_colors() {
+        let mut asset_server = AssetServer::new();
+        let mut images = Assets::new();
+        let mut atlases = Assets::new();
+
+        let texture = asset_server.add(&mut images, Image::missing("tinted.png"));
+        let atlas = TextureAtlas::from_rects(texture.clone(), vec![Rect::new(0, 0, 8, 8)]);
+        let atlas = asset_server.add(&mut atlases, atlas);
+
+        let mut world = World::new();
+        world.register_component::<Transform>();
+        world.register_component::<Sprite>();
+        world.register_component::<AtlasSprite>();
+
+        let sprite = world.spawn();
+        world.insert_component(sprite, transform(0.0));
+        world.insert_component(
+            sprite,
+            Sprite::new(texture, Rect::new(0, 0, 16, 16), SPRITE_TINT, false, false),
+        );
+
+        let atlas_sprite = world.spawn();
+        world.insert_component(atlas_sprite, transform(1.0));
+        world.insert_component(
+            atlas_sprite,
+            AtlasSprite::new(atlas, 0, ATLAS_SPRITE_TINT, false, false),
+        );
+
+        let mut sprite_batch = SpriteBatch::new();
+        draw_sprites(
+            &world,
+            &asset_server,
+            &images,
+            &atlases,
+            &DynamicAtlas::new(),
+            &mut sprite_batch,
+        );
+
+        let (_, draw_call) = sprite_batch.draw_calls().next().unwrap();
+        let colors = draw_call
+            .instance_data
+            .iter()
+            .map(InstanceData::color)
+            .collect::<Vec<_>>();
 
-        sprite_renderer.draw(sprite_instance);
+        assert_eq!(colors, vec![SPRITE_TINT, ATLAS_SPRITE_TINT]);
     }
 }
@@ -75,19 +75,19 @@ impl World {
         storage.remove(index);
     }
 
-    pub fn single<T: Component>(&self) -> Option<Ref<T>> {
+    pub fn single<T: Component>(&self) -> Option<Ref<'_, T>> {
         self.entities()
             .filter_map(|entity| self.get_component::<T>(entity))
             .next()
     }
 
-    pub fn single_mut<T: Component>(&self) -> Option<RefMut<T>> {
+    pub fn single_mut<T: Component>(&self) -> Option<RefMut<'_, T>> {
         self.entities()
             .filter_map(|entity| self.get_component_mut::<T>(entity))
             .next()
     }
 
-    pub fn get_component<T: Component>(&self, entity: Entity) -> Option<Ref<T>> {
+    pub fn get_component<T: Component>(&self, entity: Entity) -> Option<Ref<'_, T>> {
         let Some(any_vec) = self.components.get(&TypeId::of::<T>()) else {
             panic!(
                 "Trying to access a component that was not registered: '{}'",
@@ -105,7 +105,7 @@ impl World {
         storage.get(entity.id)
     }
 
-    pub fn get_component_mut<T: Component>(&self, entity: Entity) -> Option<RefMut<T>> {
+    pub fn get_component_mut<T: Component>(&self, entity: Entity) -> Option<RefMut<'_, T>> {
         let Some(any_vec) = self.components.get(&TypeId::of::<T>()) else {
             panic!(
                 "Trying to access a component that was not registered: '{}'",
@@ -145,3 +145,9 @@ impl World {
         true
     }
 }
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
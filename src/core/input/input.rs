@@ -0,0 +1,136 @@
+use std::{
+    collections::HashSet,
+    hash::{Hash, Hasher},
+};
+
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+use super::InputEvent;
+
+pub struct Input {
+    pressed_keys: HashSet<KeyCode>,
+    just_pressed_keys: HashSet<KeyCode>,
+    just_released_keys: HashSet<KeyCode>,
+
+    pressed_buttons: HashSet<MouseButton>,
+    just_pressed_buttons: HashSet<MouseButton>,
+    just_released_buttons: HashSet<MouseButton>,
+
+    cursor_position: glam::Vec2,
+    wheel_delta: glam::Vec2,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Self {
+            pressed_keys: HashSet::new(),
+            just_pressed_keys: HashSet::new(),
+            just_released_keys: HashSet::new(),
+
+            pressed_buttons: HashSet::new(),
+            just_pressed_buttons: HashSet::new(),
+            just_released_buttons: HashSet::new(),
+
+            cursor_position: glam::Vec2::ZERO,
+            wheel_delta: glam::Vec2::ZERO,
+        }
+    }
+
+    pub fn apply(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::KeyPressed(key_code) => {
+                if self.pressed_keys.insert(key_code) {
+                    self.just_pressed_keys.insert(key_code);
+                }
+            }
+            InputEvent::KeyReleased(key_code) => {
+                if self.pressed_keys.remove(&key_code) {
+                    self.just_released_keys.insert(key_code);
+                }
+            }
+            InputEvent::MouseButtonPressed(button) => {
+                if self.pressed_buttons.insert(button) {
+                    self.just_pressed_buttons.insert(button);
+                }
+            }
+            InputEvent::MouseButtonReleased(button) => {
+                if self.pressed_buttons.remove(&button) {
+                    self.just_released_buttons.insert(button);
+                }
+            }
+            InputEvent::CursorMoved { x, y } => self.cursor_position = glam::vec2(x, y),
+            InputEvent::MouseWheel { x, y } => self.wheel_delta += glam::vec2(x, y),
+        }
+    }
+
+    pub fn clear_frame(&mut self) {
+        self.just_pressed_keys.clear();
+        self.just_released_keys.clear();
+        self.just_pressed_buttons.clear();
+        self.just_released_buttons.clear();
+        self.wheel_delta = glam::Vec2::ZERO;
+    }
+
+    pub fn is_key_pressed(&self, key_code: KeyCode) -> bool {
+        self.pressed_keys.contains(&key_code)
+    }
+
+    pub fn is_key_just_pressed(&self, key_code: KeyCode) -> bool {
+        self.just_pressed_keys.contains(&key_code)
+    }
+
+    pub fn is_key_just_released(&self, key_code: KeyCode) -> bool {
+        self.just_released_keys.contains(&key_code)
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.pressed_buttons.contains(&button)
+    }
+
+    pub fn is_mouse_button_just_pressed(&self, button: MouseButton) -> bool {
+        self.just_pressed_buttons.contains(&button)
+    }
+
+    pub fn is_mouse_button_just_released(&self, button: MouseButton) -> bool {
+        self.just_released_buttons.contains(&button)
+    }
+
+    pub fn cursor_position(&self) -> glam::Vec2 {
+        self.cursor_position
+    }
+
+    pub fn wheel_delta(&self) -> glam::Vec2 {
+        self.wheel_delta
+    }
+}
+
+// Sets are hashed in sorted order so equal states hash the same regardless of insertion order.
+impl Hash for Input {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn hash_sorted<T: Ord + Hash, H: Hasher>(set: &HashSet<T>, state: &mut H) {
+            let mut items = set.iter().collect::<Vec<_>>();
+            items.sort();
+            items.hash(state);
+        }
+
+        hash_sorted(&self.pressed_keys, state);
+        hash_sorted(&self.just_pressed_keys, state);
+        hash_sorted(&self.just_released_keys, state);
+
+        hash_sorted(&self.pressed_buttons, state);
+        hash_sorted(&self.just_pressed_buttons, state);
+        hash_sorted(&self.just_released_buttons, state);
+
+        self.cursor_position
+            .to_array()
+            .map(f32::to_bits)
+            .hash(state);
+        self.wheel_delta.to_array().map(f32::to_bits).hash(state);
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,51 @@
+use winit::{
+    event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum InputEvent {
+    KeyPressed(KeyCode),
+    KeyReleased(KeyCode),
+    MouseButtonPressed(MouseButton),
+    MouseButtonReleased(MouseButton),
+    CursorMoved { x: f32, y: f32 },
+    MouseWheel { x: f32, y: f32 },
+}
+
+impl InputEvent {
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        match event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                if event.repeat {
+                    return None;
+                }
+
+                let PhysicalKey::Code(key_code) = event.physical_key else {
+                    return None;
+                };
+
+                Some(match event.state {
+                    ElementState::Pressed => Self::KeyPressed(key_code),
+                    ElementState::Released => Self::KeyReleased(key_code),
+                })
+            }
+            WindowEvent::MouseInput { state, button, .. } => Some(match state {
+                ElementState::Pressed => Self::MouseButtonPressed(*button),
+                ElementState::Released => Self::MouseButtonReleased(*button),
+            }),
+            WindowEvent::CursorMoved { position, .. } => Some(Self::CursorMoved {
+                x: position.x as f32,
+                y: position.y as f32,
+            }),
+            WindowEvent::MouseWheel { delta, .. } => Some(match delta {
+                MouseScrollDelta::LineDelta(x, y) => Self::MouseWheel { x: *x, y: *y },
+                MouseScrollDelta::PixelDelta(position) => Self::MouseWheel {
+                    x: position.x as f32,
+                    y: position.y as f32,
+                },
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#[allow(clippy::module_inception)]
+mod input;
+mod input_event;
+mod recording;
+
+pub use input::Input;
+pub use input_event::InputEvent;
+pub use recording::{InputFrame, InputPlayback, InputRecorder};
@@ -0,0 +1,90 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use super::InputEvent;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InputFrame {
+    pub tick: u64,
+    pub events: Vec<InputEvent>,
+}
+
+// Recordings are stored as one JSON encoded `InputFrame` per line, ticks without input are skipped.
+pub struct InputRecorder {
+    writer: BufWriter<File>,
+}
+
+impl InputRecorder {
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = File::create(path)?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub fn record(&mut self, tick: u64, events: &[InputEvent]) -> std::io::Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let frame = InputFrame {
+            tick,
+            events: events.to_vec(),
+        };
+
+        serde_json::to_writer(&mut self.writer, &frame)?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+pub struct InputPlayback {
+    frames: VecDeque<InputFrame>,
+}
+
+impl InputPlayback {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut frames = VecDeque::new();
+        for line in reader.lines() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            frames.push_back(serde_json::from_str::<InputFrame>(&line)?);
+        }
+
+        Ok(Self { frames })
+    }
+
+    pub fn events_for_tick(&mut self, tick: u64) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+
+        while let Some(frame) = self.frames.front() {
+            if frame.tick > tick {
+                break;
+            }
+
+            if let Some(frame) = self.frames.pop_front() {
+                events.extend(frame.events);
+            }
+        }
+
+        events
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
@@ -1,5 +1,7 @@
 pub mod assets;
 pub mod ecs;
+pub mod input;
 pub mod render;
 pub mod resources;
 pub mod utils;
+pub mod window;
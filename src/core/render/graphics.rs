@@ -1,52 +1,212 @@
-use std::sync::Arc;
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
 
 use pollster::FutureExt;
 
+use crate::core::{
+    assets::{ImageAddressMode, ImageFilter, ImageSampler},
+    window::WindowSettings,
+};
+
+const OPTIONAL_FEATURES: wgpu::Features =
+    wgpu::Features::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING
+        .union(wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING)
+        .union(wgpu::Features::TEXTURE_BINDING_ARRAY);
+
+#[derive(Debug)]
+pub enum GraphicsError {
+    CreateSurface(wgpu::CreateSurfaceError),
+    NoAdapter,
+    RequestDevice(wgpu::RequestDeviceError),
+    NoCompatibleSurfaceFormat(String),
+}
+
+impl fmt::Display for GraphicsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateSurface(err) => write!(f, "failed to create the window surface: {err}"),
+            Self::NoAdapter => write!(
+                f,
+                "no graphics adapter compatible with the window surface was found, not even a software one"
+            ),
+            Self::RequestDevice(err) => write!(f, "failed to request a graphics device: {err}"),
+            Self::NoCompatibleSurfaceFormat(adapter) => write!(
+                f,
+                "the adapter \"{adapter}\" does not support any format for the window surface"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GraphicsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CreateSurface(err) => Some(err),
+            Self::RequestDevice(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SurfaceSupport {
+    pub format: wgpu::TextureFormat,
+    pub present_modes: Vec<wgpu::PresentMode>,
+    pub alpha_mode: wgpu::CompositeAlphaMode,
+}
+
+pub struct WgpuContext {
+    pub instance: wgpu::Instance,
+    pub adapter: wgpu::Adapter,
+    pub surface: wgpu::Surface<'static>,
+    pub surface_support: SurfaceSupport,
+    pub device: Arc<wgpu::Device>,
+    pub queue: Arc<wgpu::Queue>,
+}
+
 pub fn initialize_wgpu(
     window: Arc<winit::window::Window>,
-) -> (wgpu::Surface<'static>, Arc<wgpu::Device>, Arc<wgpu::Queue>) {
+    window_settings: &WindowSettings,
+) -> Result<WgpuContext, GraphicsError> {
     let window_size = window.inner_size();
 
     let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
-    let surface = instance.create_surface(window).unwrap();
-    let adapter = instance
-        .request_adapter(&wgpu::RequestAdapterOptionsBase {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: Some(&surface),
-            force_fallback_adapter: false,
+    let surface = instance
+        .create_surface(window)
+        .map_err(GraphicsError::CreateSurface)?;
+    let adapter = request_adapter(&instance, &surface)?;
+    let surface_support = negotiate_surface_support(&surface, &adapter)?;
+    let (device, queue) = request_device(&adapter)?;
+
+    if window_size.width > 0 && window_size.height > 0 {
+        surface.configure(
+            &device,
+            &create_surface_config(window_size, &surface_support, window_settings),
+        );
+    }
+
+    Ok(WgpuContext {
+        instance,
+        adapter,
+        surface,
+        surface_support,
+        device: Arc::new(device),
+        queue: Arc::new(queue),
+    })
+}
+
+pub fn request_adapter(
+    instance: &wgpu::Instance,
+    surface: &wgpu::Surface<'static>,
+) -> Result<wgpu::Adapter, GraphicsError> {
+    [false, true]
+        .into_iter()
+        .find_map(|force_fallback_adapter| {
+            instance
+                .request_adapter(&wgpu::RequestAdapterOptionsBase {
+                    power_preference: wgpu::PowerPreference::HighPerformance,
+                    compatible_surface: Some(surface),
+                    force_fallback_adapter,
+                })
+                .block_on()
         })
-        .block_on()
-        .unwrap();
+        .ok_or(GraphicsError::NoAdapter)
+}
 
-    let (device, queue) = adapter
+pub fn request_device(
+    adapter: &wgpu::Adapter,
+) -> Result<(wgpu::Device, wgpu::Queue), GraphicsError> {
+    adapter
         .request_device(
             &wgpu::DeviceDescriptor {
                 label: Some("Request Device"),
-                required_features:
-                    wgpu::Features::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING
-                    | wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
-                    | wgpu::Features::TEXTURE_BINDING_ARRAY,
+                required_features: adapter.features() & OPTIONAL_FEATURES,
+                required_limits: wgpu::Limits::downlevel_webgl2_defaults()
+                    .using_resolution(adapter.limits()),
                 ..Default::default()
             },
             None,
         )
         .block_on()
-        .unwrap();
+        .map_err(GraphicsError::RequestDevice)
+}
 
-    surface.configure(&device, &create_surface_config(window_size));
+pub fn negotiate_surface_support(
+    surface: &wgpu::Surface<'static>,
+    adapter: &wgpu::Adapter,
+) -> Result<SurfaceSupport, GraphicsError> {
+    let capabilities = surface.get_capabilities(adapter);
 
-    (surface, Arc::new(device), Arc::new(queue))
+    let format = [
+        wgpu::TextureFormat::Bgra8UnormSrgb,
+        wgpu::TextureFormat::Rgba8UnormSrgb,
+    ]
+    .into_iter()
+    .find(|format| capabilities.formats.contains(format))
+    .or_else(|| {
+        capabilities
+            .formats
+            .iter()
+            .copied()
+            .find(|format| format.is_srgb())
+    })
+    .or_else(|| capabilities.formats.first().copied())
+    .ok_or_else(|| GraphicsError::NoCompatibleSurfaceFormat(adapter.get_info().name))?;
+
+    let alpha_mode = capabilities
+        .alpha_modes
+        .first()
+        .copied()
+        .unwrap_or(wgpu::CompositeAlphaMode::Auto);
+
+    Ok(SurfaceSupport {
+        format,
+        present_modes: capabilities.present_modes,
+        alpha_mode,
+    })
 }
 
-pub fn create_surface_config(size: winit::dpi::PhysicalSize<u32>) -> wgpu::SurfaceConfiguration {
+pub fn watch_device_lost(device: &wgpu::Device) -> Arc<Mutex<Option<String>>> {
+    let device_lost = Arc::new(Mutex::new(None));
+
+    let flag = device_lost.clone();
+    device.set_device_lost_callback(move |reason, message| {
+        if matches!(
+            reason,
+            wgpu::DeviceLostReason::Unknown | wgpu::DeviceLostReason::Destroyed
+        ) {
+            if let Ok(mut device_lost) = flag.lock() {
+                *device_lost = Some(format!("{reason:?}: {message}"));
+            }
+        }
+    });
+
+    device_lost
+}
+
+pub fn create_surface_config(
+    size: winit::dpi::PhysicalSize<u32>,
+    surface_support: &SurfaceSupport,
+    window_settings: &WindowSettings,
+) -> wgpu::SurfaceConfiguration {
+    let present_mode = match window_settings.present_mode.into() {
+        present_mode @ (wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync) => {
+            present_mode
+        }
+        present_mode if surface_support.present_modes.contains(&present_mode) => present_mode,
+        _ => wgpu::PresentMode::Fifo,
+    };
+
     wgpu::SurfaceConfiguration {
         usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-        format: wgpu::TextureFormat::Bgra8UnormSrgb,
+        format: surface_support.format,
         width: size.width,
         height: size.height,
-        desired_maximum_frame_latency: 2,
-        present_mode: wgpu::PresentMode::Fifo,
-        alpha_mode: wgpu::CompositeAlphaMode::Auto,
+        desired_maximum_frame_latency: window_settings.frame_latency,
+        present_mode,
+        alpha_mode: surface_support.alpha_mode,
         view_formats: vec![],
     }
 }
@@ -54,34 +214,78 @@ pub fn create_surface_config(size: winit::dpi::PhysicalSize<u32>) -> wgpu::Surfa
 pub fn create_texture(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
-    data: &[u8],
+    levels: &[&[u8]],
     dimensions: (u32, u32),
+    format: wgpu::TextureFormat,
+    mip_level_count: u32,
 ) -> (wgpu::Texture, wgpu::TextureView) {
-    let size = wgpu::Extent3d {
-        width: dimensions.0,
-        height: dimensions.1,
-        depth_or_array_layers: 1,
-    };
+    let mut usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
+    if mip_level_count as usize > levels.len() {
+        usage |= wgpu::TextureUsages::RENDER_ATTACHMENT;
+    }
 
     let texture_desc = &wgpu::TextureDescriptor {
         label: None,
-        size,
-        mip_level_count: 1,
+        size: wgpu::Extent3d {
+            width: dimensions.0,
+            height: dimensions.1,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count,
         sample_count: 1,
         dimension: wgpu::TextureDimension::D2,
-        format: wgpu::TextureFormat::Rgba8UnormSrgb,
-        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        format,
+        usage,
         view_formats: &[],
     };
 
-    let texture = device.create_texture(&texture_desc);
+    let texture = device.create_texture(texture_desc);
     let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
+    for (mip_level, data) in levels.iter().take(mip_level_count as usize).enumerate() {
+        let width = (dimensions.0 >> mip_level).max(1);
+        let height = (dimensions.1 >> mip_level).max(1);
+
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: mip_level as u32,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            data,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: None,
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    (texture, view)
+}
+
+pub fn write_texture_region(
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    data: &[u8],
+    origin: (u32, u32),
+    dimensions: (u32, u32),
+) {
     queue.write_texture(
         wgpu::ImageCopyTexture {
-            texture: &texture,
+            texture,
             mip_level: 0,
-            origin: wgpu::Origin3d::ZERO,
+            origin: wgpu::Origin3d {
+                x: origin.0,
+                y: origin.1,
+                z: 0,
+            },
             aspect: wgpu::TextureAspect::All,
         },
         data,
@@ -90,12 +294,50 @@ pub fn create_texture(
             bytes_per_row: Some(dimensions.0 * 4),
             rows_per_image: None,
         },
-        size,
+        wgpu::Extent3d {
+            width: dimensions.0,
+            height: dimensions.1,
+            depth_or_array_layers: 1,
+        },
     );
-
-    (texture, view)
 }
 
-pub fn create_sampler(device: &wgpu::Device) -> wgpu::Sampler {
-    device.create_sampler(&wgpu::SamplerDescriptor::default())
+pub fn create_sampler(
+    device: &wgpu::Device,
+    sampler: &ImageSampler,
+    mipmapped: bool,
+) -> wgpu::Sampler {
+    let filter_mode = |filter: ImageFilter| match filter {
+        ImageFilter::Nearest => wgpu::FilterMode::Nearest,
+        ImageFilter::Linear => wgpu::FilterMode::Linear,
+    };
+    let address_mode = |address_mode: ImageAddressMode| match address_mode {
+        ImageAddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        ImageAddressMode::Repeat => wgpu::AddressMode::Repeat,
+        ImageAddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+    };
+
+    let mipmap_filter = match mipmapped {
+        true => wgpu::FilterMode::Linear,
+        false => wgpu::FilterMode::Nearest,
+    };
+
+    // Anisotropic filtering is only valid when every filter is linear.
+    let anisotropy_clamp = match (sampler.mag_filter, sampler.min_filter, mipmap_filter) {
+        (ImageFilter::Linear, ImageFilter::Linear, wgpu::FilterMode::Linear) => {
+            sampler.anisotropy.clamp(1, 16)
+        }
+        _ => 1,
+    };
+
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("graphics:sampler"),
+        address_mode_u: address_mode(sampler.address_mode_u),
+        address_mode_v: address_mode(sampler.address_mode_v),
+        mag_filter: filter_mode(sampler.mag_filter),
+        min_filter: filter_mode(sampler.min_filter),
+        mipmap_filter,
+        anisotropy_clamp,
+        ..Default::default()
+    })
 }
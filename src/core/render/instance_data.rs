@@ -0,0 +1,47 @@
+pub const FLIP_HORIZONTAL: u32 = 1;
+pub const FLIP_VERTICAL: u32 = 2;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct InstanceData {
+    x_axis: [f32; 2],
+    y_axis: [f32; 2],
+    translation: [f32; 2],
+    uv_rect: [f32; 4],
+    color: [f32; 4],
+    flags: u32,
+}
+
+impl InstanceData {
+    pub fn new(transform: glam::Affine2, uv_rect: [f32; 4], color: [f32; 4], flags: u32) -> Self {
+        Self {
+            x_axis: transform.matrix2.x_axis.to_array(),
+            y_axis: transform.matrix2.y_axis.to_array(),
+            translation: transform.translation.to_array(),
+            uv_rect,
+            color,
+            flags,
+        }
+    }
+
+    pub fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+            1 => Float32x2,
+            2 => Float32x2,
+            3 => Float32x2,
+            4 => Float32x4,
+            5 => Float32x4,
+            6 => Uint32,
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
@@ -1,13 +1,25 @@
 pub mod graphics;
 
 mod camera;
+mod instance_data;
+mod mipmap_generator;
 mod rect;
+mod render_event;
+mod render_stats;
+mod sampler_cache;
+mod sprite_batch;
 mod sprite_instance;
 mod sprite_renderer;
 mod vertex;
 
 pub use camera::Camera;
+pub use instance_data::{InstanceData, FLIP_HORIZONTAL, FLIP_VERTICAL};
+pub use mipmap_generator::MipmapGenerator;
 pub use rect::Rect;
+pub use render_event::RenderEvent;
+pub use render_stats::RenderStats;
+pub use sampler_cache::SamplerCache;
+pub use sprite_batch::{DrawCall, SpriteBatch};
 pub use sprite_instance::SpriteInstance;
 pub use sprite_renderer::SpriteRenderer;
 pub use vertex::Vertex;
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderEvent {
+    Minimized,
+    Restored,
+    SurfaceTimeout,
+    SurfaceReconfigured,
+    DeviceLost(String),
+    DeviceRecreated,
+}
@@ -0,0 +1,14 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    pub draw_calls: usize,
+    pub sprites: usize,
+    pub bind_groups_created: usize,
+    pub samplers_created: usize,
+    pub buffers_created: usize,
+}
+
+impl RenderStats {
+    pub fn gpu_objects_created(&self) -> usize {
+        self.bind_groups_created + self.samplers_created + self.buffers_created
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use crate::core::assets::ImageSampler;
+
+use super::graphics;
+
+#[derive(Default)]
+pub struct SamplerCache {
+    samplers: HashMap<(ImageSampler, bool), wgpu::Sampler>,
+}
+
+impl SamplerCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_or_create(
+        &mut self,
+        device: &wgpu::Device,
+        sampler: ImageSampler,
+        mipmapped: bool,
+    ) -> &wgpu::Sampler {
+        self.samplers
+            .entry((sampler, mipmapped))
+            .or_insert_with(|| graphics::create_sampler(device, &sampler, mipmapped))
+    }
+
+    pub fn len(&self) -> usize {
+        self.samplers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samplers.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samplers.clear();
+    }
+}
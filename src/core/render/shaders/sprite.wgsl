@@ -1,7 +1,14 @@
 struct VertexInput {
-    @location(0) position: vec2<f32>,
-    @location(1) color: vec4<f32>,
-    @location(2) uv: vec2<f32>,
+    @location(0) corner: vec2<f32>,
+};
+
+struct InstanceInput {
+    @location(1) x_axis: vec2<f32>,
+    @location(2) y_axis: vec2<f32>,
+    @location(3) translation: vec2<f32>,
+    @location(4) uv_rect: vec4<f32>,
+    @location(5) color: vec4<f32>,
+    @location(6) flags: u32,
 };
 
 struct VertexOutput {
@@ -10,16 +17,32 @@ struct VertexOutput {
     @location(1) uv: vec2<f32>,
 };
 
+const FLIP_HORIZONTAL: u32 = 1u;
+const FLIP_VERTICAL: u32 = 2u;
+
 @group(0) @binding(0)
 var<uniform> camera_view_projection: mat4x4<f32>;
 
 @vertex
-fn vs_main(vertex: VertexInput) -> VertexOutput {
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
     var output: VertexOutput;
 
-    output.clip_position = camera_view_projection * vec4(vertex.position, 0.0, 1.0);
-    output.color = vertex.color;
-    output.uv = vertex.uv;
+    let position = instance.x_axis * vertex.corner.x
+        + instance.y_axis * vertex.corner.y
+        + instance.translation;
+
+    // The quad's top left corner samples the top left of the source rect.
+    var uv_factor = vec2((vertex.corner.x + 1.0) * 0.5, (1.0 - vertex.corner.y) * 0.5);
+    if (instance.flags & FLIP_HORIZONTAL) != 0u {
+        uv_factor.x = 1.0 - uv_factor.x;
+    }
+    if (instance.flags & FLIP_VERTICAL) != 0u {
+        uv_factor.y = 1.0 - uv_factor.y;
+    }
+
+    output.clip_position = camera_view_projection * vec4(position, 0.0, 1.0);
+    output.color = instance.color;
+    output.uv = mix(instance.uv_rect.xy, instance.uv_rect.zw, uv_factor);
 
     return output;
 }
@@ -35,4 +58,11 @@ struct FragmentInput {
 @fragment
 fn fs_main(fragment: FragmentInput) -> @location(0) vec4<f32> {
     return fragment.color * textureSample(texture, texture_sampler, fragment.uv);
-}
\ No newline at end of file
+}
+
+@fragment
+fn fs_premultiplied(fragment: FragmentInput) -> @location(0) vec4<f32> {
+    let color = vec4(fragment.color.rgb * fragment.color.a, fragment.color.a);
+
+    return color * textureSample(texture, texture_sampler, fragment.uv);
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::core::{assets::ImageSampler, utils::HandleId};
+
+use super::{InstanceData, SpriteInstance};
+
+#[derive(Default)]
+pub struct DrawCall {
+    pub instance_data: Vec<InstanceData>,
+    pub sampler: Option<ImageSampler>,
+}
+
+pub struct SpriteBatch {
+    draw_calls: HashMap<(HandleId, Option<ImageSampler>), DrawCall>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Self {
+            draw_calls: HashMap::new(),
+        }
+    }
+
+    pub fn draw(&mut self, sprite_instance: SpriteInstance) {
+        let batch = self
+            .draw_calls
+            .entry((sprite_instance.texture_id, sprite_instance.sampler))
+            .or_insert_with(|| DrawCall {
+                sampler: sprite_instance.sampler,
+                ..Default::default()
+            });
+
+        batch.instance_data.push(sprite_instance.instance_data());
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.draw_calls
+            .values()
+            .map(|draw_call| draw_call.instance_data.len())
+            .sum()
+    }
+
+    pub fn draw_calls(&self) -> impl Iterator<Item = (&HandleId, &DrawCall)> {
+        self.draw_calls
+            .iter()
+            .map(|((texture_id, _), draw_call)| (texture_id, draw_call))
+    }
+
+    pub fn len(&self) -> usize {
+        self.draw_calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.draw_calls.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.draw_calls.clear();
+    }
+}
+
+impl Default for SpriteBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
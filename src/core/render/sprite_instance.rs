@@ -1,8 +1,27 @@
-use crate::core::{assets::Image, utils::Handle};
+use crate::core::{assets::ImageSampler, utils::HandleId};
+
+use super::{InstanceData, FLIP_HORIZONTAL, FLIP_VERTICAL};
 
 pub struct SpriteInstance {
-    pub handle_image: Handle<Image>,
-    pub position: Vec<[f32; 2]>,
-    pub uv_coords: Vec<[f32; 2]>,
+    pub texture_id: HandleId,
+    pub transform: glam::Affine2,
+    pub uv_rect: [f32; 4],
     pub color: [f32; 4],
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+    pub sampler: Option<ImageSampler>,
+}
+
+impl SpriteInstance {
+    pub fn instance_data(&self) -> InstanceData {
+        let mut flags = 0;
+        if self.flip_horizontal {
+            flags |= FLIP_HORIZONTAL;
+        }
+        if self.flip_vertical {
+            flags |= FLIP_VERTICAL;
+        }
+
+        InstanceData::new(self.transform, self.uv_rect, self.color, flags)
+    }
 }
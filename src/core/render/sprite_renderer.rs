@@ -1,37 +1,51 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use wgpu::include_wgsl;
 
-use crate::core::{resources::Resources, utils::HandleId};
+use crate::core::{assets::ImageSampler, resources::Resources};
 
-use super::{graphics, Camera, SpriteInstance, Vertex};
+use super::{
+    Camera, InstanceData, Rect, RenderStats, SamplerCache, SpriteBatch, SpriteInstance, Vertex,
+};
 
-#[derive(Default)]
-pub struct DrawCall {
-    vertex_data: Vec<Vertex>,
-    index_data: Vec<u16>,
-    instances: u16,
-}
+const QUAD_VERTICES: [Vertex; 4] = [
+    Vertex::new([-1.0, 1.0]),
+    Vertex::new([-1.0, -1.0]),
+    Vertex::new([1.0, -1.0]),
+    Vertex::new([1.0, 1.0]),
+];
+const QUAD_INDICES: [u16; 6] = [0, 1, 2, 2, 3, 0];
+const INITIAL_INSTANCE_CAPACITY: usize = 2048;
 
 pub struct SpriteRenderer {
     device: Arc<wgpu::Device>,
     queue: Arc<wgpu::Queue>,
     camera: Camera,
+    viewport: Option<Rect>,
     texture_bind_group_layout: wgpu::BindGroupLayout,
 
-    draw_calls: HashMap<HandleId, DrawCall>,
+    batch: SpriteBatch,
+    stats: RenderStats,
+
+    samplers: SamplerCache,
+    default_sampler: ImageSampler,
 
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    instance_capacity: usize,
     pipeline: wgpu::RenderPipeline,
+    premultiplied_pipeline: wgpu::RenderPipeline,
 }
 
 impl SpriteRenderer {
-    pub fn new(device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>) -> Self {
+    pub fn new(
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        format: wgpu::TextureFormat,
+    ) -> Self {
         let camera = Camera::new(&device, queue.clone());
 
-        let draw_calls = HashMap::new();
-
         let texture_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("SpriteRenderer:texture_bind_group_layout"),
@@ -63,103 +77,112 @@ impl SpriteRenderer {
 
         let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("SpriteRenderer:vertex_buffer"),
-            size: (4 * std::mem::size_of::<Vertex>() * 2048) as wgpu::BufferAddress,
+            size: std::mem::size_of_val(&QUAD_VERTICES) as wgpu::BufferAddress,
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
+        queue.write_buffer(&vertex_buffer, 0, bytemuck::cast_slice(&QUAD_VERTICES));
 
         let index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("SpriteRenderer:index_buffer"),
-            size: (6 * std::mem::size_of::<u16>() * 2048) as wgpu::BufferAddress,
+            size: std::mem::size_of_val(&QUAD_INDICES) as wgpu::BufferAddress,
             usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
+        queue.write_buffer(&index_buffer, 0, bytemuck::cast_slice(&QUAD_INDICES));
+
+        let instance_buffer = create_instance_buffer(&device, INITIAL_INSTANCE_CAPACITY);
 
         let shader = device.create_shader_module(include_wgsl!("shaders/sprite.wgsl"));
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("SpriteRenderer:pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                compilation_options: Default::default(),
-                buffers: &[Vertex::desc()],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                compilation_options: Default::default(),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: Default::default(),
-            multisample: Default::default(),
-            depth_stencil: None,
-            multiview: None,
-            cache: None,
-        });
+        let pipeline = create_pipeline(
+            &device,
+            &shader,
+            &pipeline_layout,
+            format,
+            "fs_main",
+            wgpu::BlendState::ALPHA_BLENDING,
+        );
+        let premultiplied_pipeline = create_pipeline(
+            &device,
+            &shader,
+            &pipeline_layout,
+            format,
+            "fs_premultiplied",
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        );
 
         Self {
             device,
             queue,
             camera,
+            viewport: None,
             texture_bind_group_layout,
 
-            draw_calls,
+            batch: SpriteBatch::new(),
+            stats: RenderStats::default(),
+
+            samplers: SamplerCache::new(),
+            default_sampler: ImageSampler::nearest(),
 
             vertex_buffer,
             index_buffer,
+            instance_buffer,
+            instance_capacity: INITIAL_INSTANCE_CAPACITY,
             pipeline,
+            premultiplied_pipeline,
         }
     }
 
     pub fn draw(&mut self, sprite_instance: SpriteInstance) {
-        let batch = self
-            .draw_calls
-            .entry(sprite_instance.handle_image.id())
-            .or_default();
-
-        let vertex_data: Vec<Vertex> = sprite_instance
-            .position
-            .iter()
-            .zip(sprite_instance.uv_coords)
-            .map(|(&xy, uv)| Vertex::new(xy, sprite_instance.color, uv))
-            .collect();
-
-        let index_data = vec![
-            batch.instances * 4,
-            batch.instances * 4 + 1,
-            batch.instances * 4 + 2,
-            batch.instances * 4 + 2,
-            batch.instances * 4 + 3,
-            batch.instances * 4,
-        ];
-
-        batch.instances += 1;
-        batch.vertex_data.extend(vertex_data);
-        batch.index_data.extend(index_data);
+        self.batch.draw(sprite_instance);
+    }
+
+    pub fn batch_mut(&mut self) -> &mut SpriteBatch {
+        &mut self.batch
+    }
+
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    pub fn default_sampler(&self) -> ImageSampler {
+        self.default_sampler
+    }
+
+    pub fn set_default_sampler(&mut self, sampler: ImageSampler) {
+        self.default_sampler = sampler;
     }
 
     pub fn update_view_projection(&mut self, view_projection: glam::Mat4) {
         self.camera.update_view_projection(view_projection);
     }
 
+    pub fn update_viewport(&mut self, viewport: Rect) {
+        self.viewport = Some(viewport);
+    }
+
     pub fn render(
         &mut self,
-        resources: &Resources,
+        resources: &mut Resources,
         view: &wgpu::TextureView,
         encoder: &mut wgpu::CommandEncoder,
     ) {
+        let mut stats = RenderStats::default();
+
+        let instance_count = self.batch.instance_count();
+        if instance_count > self.instance_capacity {
+            self.instance_capacity = instance_count.next_power_of_two();
+            self.instance_buffer = create_instance_buffer(&self.device, self.instance_capacity);
+            stats.buffers_created += 1;
+        }
+
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("SpriteRenderer:render_pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
                     store: wgpu::StoreOp::Store,
                 },
                 resolve_target: None,
@@ -167,52 +190,142 @@ impl SpriteRenderer {
             ..Default::default()
         });
 
-        render_pass.set_pipeline(&self.pipeline);
+        if let Some(viewport) = self
+            .viewport
+            .filter(|viewport| viewport.w > 0 && viewport.h > 0)
+        {
+            render_pass.set_viewport(
+                viewport.x as f32,
+                viewport.y as f32,
+                viewport.w as f32,
+                viewport.h as f32,
+                0.0,
+                1.0,
+            );
+        }
 
         render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
-
-        let mut offsets = (0, 0);
-        for (handle_id, draw_call) in &self.draw_calls {
-            let texture = resources.textures.get(handle_id).unwrap();
-
-            let texture_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some(format!("SpriteRenderer:{}", handle_id.id()).as_str()),
-                layout: &self.texture_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&texture.view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&graphics::create_sampler(
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+
+        let sampler_count = self.samplers.len();
+        let mut offset = 0;
+        let mut premultiplied_alpha = None;
+        for (handle_id, draw_call) in self.batch.draw_calls() {
+            let Some(texture) = resources.textures.get(handle_id) else {
+                log::debug!(
+                    "Skipping {} sprites with no texture uploaded",
+                    draw_call.instance_data.len()
+                );
+                continue;
+            };
+
+            if premultiplied_alpha != Some(texture.premultiplied_alpha) {
+                premultiplied_alpha = Some(texture.premultiplied_alpha);
+
+                render_pass.set_pipeline(match texture.premultiplied_alpha {
+                    true => &self.premultiplied_pipeline,
+                    false => &self.pipeline,
+                });
+            }
+
+            let sampler = draw_call
+                .sampler
+                .or(texture.sampler)
+                .unwrap_or(self.default_sampler);
+            let texture_bind_group =
+                resources
+                    .bind_groups
+                    .get_or_create(*handle_id, sampler, || {
+                        let texture_sampler = self.samplers.get_or_create(
                             &self.device,
-                        )),
-                    },
-                ],
-            });
+                            sampler,
+                            texture.is_mipmapped(),
+                        );
+                        stats.bind_groups_created += 1;
+
+                        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                            label: Some("SpriteRenderer:texture_bind_group"),
+                            layout: &self.texture_bind_group_layout,
+                            entries: &[
+                                wgpu::BindGroupEntry {
+                                    binding: 0,
+                                    resource: wgpu::BindingResource::TextureView(&texture.view),
+                                },
+                                wgpu::BindGroupEntry {
+                                    binding: 1,
+                                    resource: wgpu::BindingResource::Sampler(texture_sampler),
+                                },
+                            ],
+                        })
+                    });
+
+            render_pass.set_bind_group(1, texture_bind_group, &[]);
+
+            let instance_data = bytemuck::cast_slice(&draw_call.instance_data);
 
-            render_pass.set_bind_group(1, &texture_bind_group, &[]);
-
-            let vertex_data = bytemuck::cast_slice(&draw_call.vertex_data);
-            let index_data = bytemuck::cast_slice(&draw_call.index_data);
-
-            self.queue
-                .write_buffer(&self.vertex_buffer, offsets.0, vertex_data);
             self.queue
-                .write_buffer(&self.index_buffer, offsets.1, index_data);
+                .write_buffer(&self.instance_buffer, offset, instance_data);
 
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(offsets.0..));
-            render_pass.set_index_buffer(
-                self.index_buffer.slice(offsets.1..),
-                wgpu::IndexFormat::Uint16,
+            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(offset..));
+            render_pass.draw_indexed(
+                0..QUAD_INDICES.len() as u32,
+                0,
+                0..draw_call.instance_data.len() as u32,
             );
-            render_pass.draw_indexed(0..draw_call.index_data.len() as u32, 0, 0..1);
 
-            offsets.0 += std::mem::size_of_val(vertex_data) as u64;
-            offsets.1 += std::mem::size_of_val(index_data) as u64;
+            offset += std::mem::size_of_val(instance_data) as u64;
+
+            stats.draw_calls += 1;
+            stats.sprites += draw_call.instance_data.len();
         }
 
-        self.draw_calls.clear();
+        stats.samplers_created = self.samplers.len().saturating_sub(sampler_count);
+        self.stats = stats;
+        self.batch.clear();
     }
 }
+
+fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("SpriteRenderer:instance_buffer"),
+        size: (std::mem::size_of::<InstanceData>() * capacity) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    pipeline_layout: &wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    fragment_entry_point: &str,
+    blend: wgpu::BlendState,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("SpriteRenderer:pipeline"),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_main"),
+            compilation_options: Default::default(),
+            buffers: &[Vertex::desc(), InstanceData::desc()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some(fragment_entry_point),
+            compilation_options: Default::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: Default::default(),
+        multisample: Default::default(),
+        depth_stencil: None,
+        multiview: None,
+        cache: None,
+    })
+}
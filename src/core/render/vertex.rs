@@ -1,19 +1,16 @@
 #[repr(C)]
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
-    pos: [f32; 2],
-    color: [f32; 4],
-    uv: [f32; 2],
+    corner: [f32; 2],
 }
 
 impl Vertex {
-    pub fn new(pos: [f32; 2], color: [f32; 4], uv: [f32; 2]) -> Self {
-        Self { pos, color, uv }
+    pub const fn new(corner: [f32; 2]) -> Self {
+        Self { corner }
     }
 
     pub fn desc() -> wgpu::VertexBufferLayout<'static> {
-        const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
-            wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4, 2 => Float32x2];
+        const ATTRIBUTES: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x2];
 
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
@@ -1,5 +1,6 @@
 pub mod specifications;
 
+#[allow(clippy::module_inception)]
 mod resources;
 
 pub use resources::Resources;
@@ -1,15 +1,42 @@
-use crate::core::utils::{Cache, HandleId};
+use std::sync::Arc;
 
-use super::specifications::GpuImage;
+use crate::core::{
+    render::MipmapGenerator,
+    utils::{Cache, HandleId},
+};
+
+use super::specifications::{BindGroupCache, DynamicAtlas, GpuImage};
 
 pub struct Resources {
     pub textures: Cache<HandleId, GpuImage>,
+    pub bind_groups: BindGroupCache,
+    pub atlas: DynamicAtlas,
+    pub mipmap_generator: Option<MipmapGenerator>,
 }
 
 impl Resources {
     pub fn new() -> Self {
         Self {
             textures: Cache::new(),
+            bind_groups: BindGroupCache::new(),
+            atlas: DynamicAtlas::new(),
+            mipmap_generator: None,
         }
     }
+
+    pub fn insert_texture(&mut self, handle_id: HandleId, gpu_image: GpuImage) {
+        self.bind_groups.invalidate(&handle_id);
+        self.textures.insert(handle_id, gpu_image);
+    }
+
+    pub fn remove_texture(&mut self, handle_id: &HandleId) -> Option<Arc<GpuImage>> {
+        self.bind_groups.invalidate(handle_id);
+        self.textures.remove(handle_id)
+    }
+}
+
+impl Default for Resources {
+    fn default() -> Self {
+        Self::new()
+    }
 }
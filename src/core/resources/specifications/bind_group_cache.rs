@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use crate::core::{assets::ImageSampler, utils::HandleId};
+
+#[derive(Default)]
+pub struct BindGroupCache {
+    bind_groups: HashMap<HandleId, HashMap<ImageSampler, wgpu::BindGroup>>,
+}
+
+impl BindGroupCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_or_create(
+        &mut self,
+        handle_id: HandleId,
+        sampler: ImageSampler,
+        create: impl FnOnce() -> wgpu::BindGroup,
+    ) -> &wgpu::BindGroup {
+        self.bind_groups
+            .entry(handle_id)
+            .or_default()
+            .entry(sampler)
+            .or_insert_with(create)
+    }
+
+    pub fn invalidate(&mut self, handle_id: &HandleId) {
+        self.bind_groups.remove(handle_id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.bind_groups.values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bind_groups.values().all(HashMap::is_empty)
+    }
+
+    pub fn clear(&mut self) {
+        self.bind_groups.clear();
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::core::{
+    assets::Image,
+    render::Rect,
+    utils::{Handle, HandleId, SkylinePacker},
+};
+
+pub const ATLAS_PAGE_SIZE: u32 = 2048;
+const MAX_PACKED_SIZE: u32 = 1024;
+const PADDING: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasPlacement {
+    pub page: HandleId,
+    pub rect: Rect,
+}
+
+#[derive(Debug)]
+struct AtlasPage {
+    handle: Handle<Image>,
+    packer: SkylinePacker,
+}
+
+#[derive(Debug)]
+pub struct DynamicAtlas {
+    pages: Vec<AtlasPage>,
+    placements: HashMap<HandleId, AtlasPlacement>,
+    wasted_area: u64,
+}
+
+impl DynamicAtlas {
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            placements: HashMap::new(),
+            wasted_area: 0,
+        }
+    }
+
+    pub fn can_pack(dimensions: (u32, u32)) -> bool {
+        dimensions.0 <= MAX_PACKED_SIZE && dimensions.1 <= MAX_PACKED_SIZE
+    }
+
+    pub fn get(&self, handle_id: &HandleId) -> Option<AtlasPlacement> {
+        self.placements.get(handle_id).copied()
+    }
+
+    pub fn allocate(
+        &mut self,
+        handle_id: HandleId,
+        dimensions: (u32, u32),
+    ) -> Option<AtlasPlacement> {
+        let (width, height) = dimensions;
+
+        let placement = self.pages.iter_mut().find_map(|page| {
+            let (x, y) = page.packer.pack(width + PADDING, height + PADDING)?;
+
+            Some(AtlasPlacement {
+                page: page.handle.id(),
+                rect: Rect::new(x, y, width, height),
+            })
+        })?;
+
+        self.placements.insert(handle_id, placement);
+
+        Some(placement)
+    }
+
+    pub fn remove(&mut self, handle_id: &HandleId) -> Option<AtlasPlacement> {
+        let placement = self.placements.remove(handle_id)?;
+        self.wasted_area +=
+            u64::from(placement.rect.w + PADDING) * u64::from(placement.rect.h + PADDING);
+
+        Some(placement)
+    }
+
+    pub fn add_page(&mut self, handle: Handle<Image>) {
+        self.pages.push(AtlasPage {
+            handle,
+            packer: SkylinePacker::new(ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE),
+        });
+    }
+
+    pub fn clear(&mut self) -> Vec<HandleId> {
+        for page in &mut self.pages {
+            page.packer.clear();
+        }
+
+        self.wasted_area = 0;
+        self.placements
+            .drain()
+            .map(|(handle_id, _)| handle_id)
+            .collect()
+    }
+
+    pub fn page_ids(&self) -> impl Iterator<Item = HandleId> + '_ {
+        self.pages.iter().map(|page| page.handle.id())
+    }
+
+    pub fn remove_empty_pages(&mut self) -> Vec<Handle<Image>> {
+        let (pages, empty) = std::mem::take(&mut self.pages)
+            .into_iter()
+            .partition::<Vec<_>, _>(|page| {
+                self.placements
+                    .values()
+                    .any(|placement| placement.page == page.handle.id())
+            });
+        self.pages = pages;
+
+        empty.into_iter().map(|page| page.handle).collect()
+    }
+
+    pub fn wasted_area(&self) -> u64 {
+        self.wasted_area
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.placements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.placements.is_empty()
+    }
+}
+
+impl Default for DynamicAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_pages_are_removed_after_a_repack() {
+        let mut atlas = DynamicAtlas::new();
+        atlas.add_page(Handle::weak(HandleId::new(0, 0)));
+        atlas.add_page(Handle::weak(HandleId::new(1, 0)));
+
+        let image = HandleId::new(2, 0);
+        atlas.allocate(
+            image,
+            (ATLAS_PAGE_SIZE - PADDING, ATLAS_PAGE_SIZE - PADDING),
+        );
+        atlas.clear();
+        let placement = atlas.allocate(image, (16, 16)).unwrap();
+
+        let removed = atlas.remove_empty_pages();
+
+        assert_eq!(placement.page, HandleId::new(0, 0));
+        assert_eq!(removed, vec![Handle::weak(HandleId::new(1, 0))]);
+        assert_eq!(atlas.page_count(), 1);
+    }
+}
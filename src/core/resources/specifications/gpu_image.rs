@@ -1,11 +1,34 @@
+use crate::core::assets::ImageSampler;
+
 #[derive(Debug)]
 pub struct GpuImage {
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
+    pub premultiplied_alpha: bool,
+    pub sampler: Option<ImageSampler>,
 }
 
 impl GpuImage {
     pub fn new(texture: wgpu::Texture, view: wgpu::TextureView) -> Self {
-        Self { texture, view }
+        Self {
+            texture,
+            view,
+            premultiplied_alpha: false,
+            sampler: None,
+        }
+    }
+
+    pub fn with_premultiplied_alpha(mut self, premultiplied_alpha: bool) -> Self {
+        self.premultiplied_alpha = premultiplied_alpha;
+        self
+    }
+
+    pub fn with_sampler(mut self, sampler: Option<ImageSampler>) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    pub fn is_mipmapped(&self) -> bool {
+        self.texture.mip_level_count() > 1
     }
 }
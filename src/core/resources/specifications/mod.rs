@@ -1,3 +1,7 @@
+mod bind_group_cache;
+mod dynamic_atlas;
 mod gpu_image;
 
+pub use bind_group_cache::BindGroupCache;
+pub use dynamic_atlas::{AtlasPlacement, DynamicAtlas, ATLAS_PAGE_SIZE};
 pub use gpu_image::GpuImage;
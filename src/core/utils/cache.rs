@@ -12,7 +12,7 @@ impl<K: PartialEq + Eq + std::hash::Hash, V> Cache<K, V> {
     }
 
     pub fn insert(&mut self, key: K, item: V) {
-        self.items.entry(key).or_insert(Arc::new(item));
+        self.items.insert(key, Arc::new(item));
     }
 
     pub fn remove(&mut self, key: &K) -> Option<Arc<V>> {
@@ -26,4 +26,22 @@ impl<K: PartialEq + Eq + std::hash::Hash, V> Cache<K, V> {
     pub fn get(&self, key: &K) -> Option<&Arc<V>> {
         self.items.get(key)
     }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &Arc<V>)> {
+        self.items.iter()
+    }
+}
+
+impl<K: PartialEq + Eq + std::hash::Hash, V> Default for Cache<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,35 @@
+use std::collections::VecDeque;
+
+pub struct Events<T> {
+    events: VecDeque<T>,
+}
+
+impl<T> Events<T> {
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.events.push_back(event);
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.events.drain(..)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
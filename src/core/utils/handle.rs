@@ -1,37 +1,85 @@
 use std::{
     hash::{Hash, Hasher},
     marker::PhantomData,
+    sync::{mpsc, Arc},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct HandleId(String);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HandleId {
+    index: u32,
+    generation: u32,
+}
 
 impl HandleId {
-    pub fn new(identifier: &str) -> Self {
-        Self(identifier.to_string())
+    pub fn new(index: u32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
     }
 
-    pub fn id(&self) -> &str {
-        &self.0
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+#[derive(Debug)]
+pub struct StrongHandle {
+    id: HandleId,
+    drop_sender: mpsc::Sender<HandleId>,
+}
+
+impl StrongHandle {
+    pub fn new(id: HandleId, drop_sender: mpsc::Sender<HandleId>) -> Self {
+        Self { id, drop_sender }
+    }
+
+    pub fn id(&self) -> HandleId {
+        self.id
+    }
+}
+
+impl Drop for StrongHandle {
+    fn drop(&mut self) {
+        let _ = self.drop_sender.send(self.id);
     }
 }
 
 #[derive(Debug)]
 pub struct Handle<T> {
     id: HandleId,
+    strong: Option<Arc<StrongHandle>>,
     _marker: PhantomData<T>,
 }
 
 impl<T> Handle<T> {
-    pub fn new(id: HandleId) -> Self {
+    pub fn strong(strong_handle: Arc<StrongHandle>) -> Self {
+        Self {
+            id: strong_handle.id,
+            strong: Some(strong_handle),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn weak(id: HandleId) -> Self {
         Self {
             id,
+            strong: None,
             _marker: PhantomData,
         }
     }
 
     pub fn id(&self) -> HandleId {
-        self.id.clone()
+        self.id
+    }
+
+    pub fn is_strong(&self) -> bool {
+        self.strong.is_some()
+    }
+
+    pub fn clone_weak(&self) -> Self {
+        Self::weak(self.id)
     }
 }
 
@@ -52,7 +100,8 @@ impl<T> Hash for Handle<T> {
 impl<T> Clone for Handle<T> {
     fn clone(&self) -> Self {
         Self {
-            id: self.id.clone(),
+            id: self.id,
+            strong: self.strong.clone(),
             _marker: PhantomData,
         }
     }
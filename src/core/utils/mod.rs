@@ -1,5 +1,9 @@
 mod cache;
+mod events;
 mod handle;
+mod skyline_packer;
 
 pub use cache::Cache;
-pub use handle::{Handle, HandleId};
+pub use events::Events;
+pub use handle::{Handle, HandleId, StrongHandle};
+pub use skyline_packer::SkylinePacker;
@@ -0,0 +1,167 @@
+#[derive(Debug, Clone, Copy)]
+struct SkylineNode {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+#[derive(Debug)]
+pub struct SkylinePacker {
+    width: u32,
+    height: u32,
+    skyline: Vec<SkylineNode>,
+}
+
+impl SkylinePacker {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            skyline: vec![SkylineNode { x: 0, y: 0, width }],
+        }
+    }
+
+    pub fn pack(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (index, x, y) = (0..self.skyline.len())
+            .filter_map(|index| {
+                let y = self.fit(index, width, height)?;
+
+                Some((index, self.skyline[index].x, y))
+            })
+            .min_by_key(|&(_, x, y)| (y + height, x))?;
+
+        self.skyline.insert(
+            index,
+            SkylineNode {
+                x,
+                y: y + height,
+                width,
+            },
+        );
+
+        let right = x + width;
+        while let Some(node) = self.skyline.get_mut(index + 1) {
+            if node.x >= right {
+                break;
+            }
+
+            let overlap = right - node.x;
+            if node.width > overlap {
+                node.x += overlap;
+                node.width -= overlap;
+                break;
+            }
+
+            self.skyline.remove(index + 1);
+        }
+
+        self.merge();
+
+        Some((x, y))
+    }
+
+    pub fn clear(&mut self) {
+        self.skyline = vec![SkylineNode {
+            x: 0,
+            y: 0,
+            width: self.width,
+        }];
+    }
+
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.skyline[index].x;
+        if x + width > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut covered = 0;
+        for node in &self.skyline[index..] {
+            if covered >= width {
+                break;
+            }
+
+            y = y.max(node.y);
+            if y + height > self.height {
+                return None;
+            }
+
+            covered += node.width;
+        }
+
+        (covered >= width).then_some(y)
+    }
+
+    fn merge(&mut self) {
+        let mut index = 0;
+        while index + 1 < self.skyline.len() {
+            if self.skyline[index].y == self.skyline[index + 1].y {
+                self.skyline[index].width += self.skyline[index + 1].width;
+                self.skyline.remove(index + 1);
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: (u32, u32, u32, u32), b: (u32, u32, u32, u32)) -> bool {
+        a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+    }
+
+    #[test]
+    fn packed_rects_stay_in_bounds_and_never_overlap() {
+        let mut packer = SkylinePacker::new(256, 256);
+        let mut placed = Vec::new();
+
+        for index in 0..200u32 {
+            let (width, height) = (8 + index * 7 % 29, 8 + index * 13 % 23);
+
+            if let Some((x, y)) = packer.pack(width, height) {
+                assert!(x + width <= 256 && y + height <= 256);
+                placed.push((x, y, width, height));
+            }
+        }
+
+        assert!(placed.len() > 50);
+        for (index, &a) in placed.iter().enumerate() {
+            for &b in &placed[index + 1..] {
+                assert!(!overlaps(a, b), "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn fills_a_page_exactly_and_rejects_what_is_left() {
+        let mut packer = SkylinePacker::new(64, 64);
+
+        assert_eq!(packer.pack(32, 32), Some((0, 0)));
+        assert_eq!(packer.pack(32, 32), Some((32, 0)));
+        assert_eq!(packer.pack(64, 32), Some((0, 32)));
+        assert_eq!(packer.pack(1, 1), None);
+    }
+
+    #[test]
+    fn rejects_rects_larger_than_the_page() {
+        let mut packer = SkylinePacker::new(64, 64);
+
+        assert_eq!(packer.pack(65, 1), None);
+        assert_eq!(packer.pack(1, 65), None);
+        assert_eq!(packer.pack(64, 64), Some((0, 0)));
+    }
+
+    #[test]
+    fn clear_makes_the_whole_page_available_again() {
+        let mut packer = SkylinePacker::new(64, 64);
+        packer.pack(48, 48);
+
+        assert_eq!(packer.pack(64, 64), None);
+
+        packer.clear();
+        assert_eq!(packer.pack(64, 64), Some((0, 0)));
+    }
+}
@@ -0,0 +1,3 @@
+mod window_settings;
+
+pub use window_settings::{PresentMode, WindowMode, WindowSettings};
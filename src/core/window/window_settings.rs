@@ -0,0 +1,132 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use winit::{
+    dpi::PhysicalSize,
+    monitor::MonitorHandle,
+    window::{Fullscreen, Window, WindowAttributes},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum WindowMode {
+    Windowed,
+    BorderlessFullscreen,
+    Fullscreen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PresentMode {
+    AutoVsync,
+    AutoNoVsync,
+    Fifo,
+    FifoRelaxed,
+    Immediate,
+    Mailbox,
+}
+
+impl From<PresentMode> for wgpu::PresentMode {
+    fn from(present_mode: PresentMode) -> Self {
+        match present_mode {
+            PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub title: String,
+    pub size: (u32, u32),
+    pub min_size: Option<(u32, u32)>,
+    pub resizable: bool,
+    pub mode: WindowMode,
+    pub present_mode: PresentMode,
+    pub frame_latency: u32,
+}
+
+impl WindowSettings {
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let file = File::create(path)?;
+
+        Ok(serde_json::to_writer_pretty(file, self)?)
+    }
+
+    pub fn window_attributes(&self, monitor: Option<MonitorHandle>) -> WindowAttributes {
+        let mut window_attributes = Window::default_attributes()
+            .with_title(self.title.as_str())
+            .with_inner_size(PhysicalSize::new(self.size.0, self.size.1))
+            .with_resizable(self.resizable)
+            .with_fullscreen(self.fullscreen(monitor));
+
+        if let Some((width, height)) = self.min_size {
+            window_attributes =
+                window_attributes.with_min_inner_size(PhysicalSize::new(width, height));
+        }
+
+        window_attributes
+    }
+
+    pub fn fullscreen(&self, monitor: Option<MonitorHandle>) -> Option<Fullscreen> {
+        match self.mode {
+            WindowMode::Windowed => None,
+            WindowMode::BorderlessFullscreen => Some(Fullscreen::Borderless(monitor)),
+            WindowMode::Fullscreen => {
+                let video_mode = monitor.as_ref().and_then(|monitor| {
+                    monitor.video_modes().max_by_key(|video_mode| {
+                        let size = video_mode.size();
+
+                        (
+                            size.width * size.height,
+                            video_mode.refresh_rate_millihertz(),
+                        )
+                    })
+                });
+
+                match video_mode {
+                    Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                    None => Some(Fullscreen::Borderless(monitor)),
+                }
+            }
+        }
+    }
+
+    pub fn apply(&self, window: &Window) -> Option<PhysicalSize<u32>> {
+        window.set_title(&self.title);
+        window.set_resizable(self.resizable);
+        window.set_min_inner_size(
+            self.min_size
+                .map(|(width, height)| PhysicalSize::new(width, height)),
+        );
+        window.set_fullscreen(self.fullscreen(window.current_monitor()));
+
+        if self.mode != WindowMode::Windowed {
+            return None;
+        }
+
+        window.request_inner_size(PhysicalSize::new(self.size.0, self.size.1))
+    }
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            title: "Corvus".to_string(),
+            size: (1280, 720),
+            min_size: None,
+            resizable: true,
+            mode: WindowMode::Windowed,
+            present_mode: PresentMode::Fifo,
+            frame_latency: 2,
+        }
+    }
+}
@@ -1,91 +1,196 @@
-use std::sync::Arc;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use winit::{event::WindowEvent, window::Window};
 
 use crate::core::{
-    assets::{AssetServer, Assets, Image},
-    ecs::{
-        components::{OrthoCamera, Sprite, Transform},
-        systems::{asset_system, render_system},
-        World,
+    assets::{
+        loaders::{ImageLoader, SpriteSheetLoader},
+        AnimationClip, AssetEvent, AssetServer, AssetStorage, Image, TextureAtlas,
     },
-    render::{graphics, Rect, SpriteRenderer},
+    ecs::systems::{asset_system, render_system},
+    input::{Input, InputEvent},
+    render::{graphics, RenderEvent, RenderStats, SpriteRenderer},
     resources::Resources,
+    utils::Events,
+    window::WindowSettings,
 };
 
+use super::{scene, Simulation};
+
+const FIXED_TIMESTEP: Duration = Duration::from_micros(16_667);
+const MAX_TICKS_PER_UPDATE: u32 = 5;
+const MIN_DEVICE_RETRY_DELAY: Duration = Duration::from_millis(250);
+const MAX_DEVICE_RETRY_DELAY: Duration = Duration::from_secs(8);
+
 pub struct Game {
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
     surface: wgpu::Surface<'static>,
+    surface_support: graphics::SurfaceSupport,
     device: Arc<wgpu::Device>,
     queue: Arc<wgpu::Queue>,
+    device_lost: Arc<Mutex<Option<String>>>,
+    device_retry_at: Option<Instant>,
+    device_retry_delay: Duration,
+    minimized: bool,
+    render_events: Events<RenderEvent>,
 
     sprite_renderer: SpriteRenderer,
 
-    assets: Assets,
+    assets: AssetStorage,
     resources: Resources,
     asset_server: AssetServer,
-    world: World,
+    simulation: Simulation,
+
+    accumulator: Duration,
+    last_update: Instant,
 
     window: Arc<Window>,
+    window_settings: WindowSettings,
 }
 
 impl Game {
-    pub fn new(window: Window) -> Self {
+    pub fn new(
+        window: Window,
+        window_settings: WindowSettings,
+    ) -> Result<Self, graphics::GraphicsError> {
         let window = std::sync::Arc::new(window);
-        let (surface, device, queue) = graphics::initialize_wgpu(window.clone());
+        let graphics::WgpuContext {
+            instance,
+            adapter,
+            surface,
+            surface_support,
+            device,
+            queue,
+        } = graphics::initialize_wgpu(window.clone(), &window_settings)?;
+        let device_lost = graphics::watch_device_lost(&device);
 
-        let sprite_renderer = SpriteRenderer::new(device.clone(), queue.clone());
+        let sprite_renderer =
+            SpriteRenderer::new(device.clone(), queue.clone(), surface_support.format);
 
-        let assets = Assets::new();
-        let resources = Resources::new();
-        let mut asset_server = AssetServer::new();
+        let mut assets = AssetStorage::new();
+        assets.register::<Image>();
+        assets.register::<TextureAtlas>();
+        assets.register::<AnimationClip>();
 
-        let mut world = World::new();
-        world.register_component::<Transform>();
-        world.register_component::<Sprite>();
-        world.register_component::<OrthoCamera>();
+        let mut resources = Resources::new();
+        let mut asset_server = AssetServer::new();
+        asset_server.register_loader(ImageLoader);
+        asset_server.register_loader(SpriteSheetLoader);
+        asset_server.watch_for_changes(cfg!(debug_assertions));
 
-        let c = world.spawn();
-        world.insert_component(
-            c,
-            OrthoCamera::new(glam::vec2(0.0, 0.0), window.inner_size(), 1.0),
+        asset_system::insert_missing_texture(
+            &device,
+            &queue,
+            &asset_server,
+            assets.get_mut::<Image>(),
+            &mut resources,
         );
 
-        let e = world.spawn();
-        world.insert_component(
-            e,
-            Transform::new(
-                glam::vec3(16.0, 0.0, 0.0),
-                glam::vec2(1.0, 1.0),
-                0.0,
-                glam::vec2(0.0, 1.0),
-            ),
-        );
+        let world = scene::create_world(&mut asset_server, window.inner_size());
 
-        world.insert_component(
-            e,
-            Sprite::new(
-                asset_server.load::<Image>("assets/character/idle.png"),
-                Rect::new(32, 32, 16, 16),
-                [1.0, 1.0, 1.0, 1.0],
-                false,
-                false,
-            ),
-        );
+        if let Err(err) = asset_server.load_group_manifest("groups.json") {
+            log::error!("Failed to load asset groups: {err}");
+        }
 
-        Self {
+        Ok(Self {
+            instance,
+            adapter,
             surface,
+            surface_support,
             device,
             queue,
+            device_lost,
+            device_retry_at: None,
+            device_retry_delay: MIN_DEVICE_RETRY_DELAY,
+            minimized: false,
+            render_events: Events::new(),
 
             sprite_renderer,
 
             assets,
             resources,
             asset_server,
-            world,
+            simulation: Simulation::new(world),
+
+            accumulator: Duration::ZERO,
+            last_update: Instant::now(),
 
             window,
+            window_settings,
+        })
+    }
+
+    pub fn window_settings(&self) -> &WindowSettings {
+        &self.window_settings
+    }
+
+    pub fn set_window_settings(&mut self, window_settings: WindowSettings) {
+        self.window_settings = window_settings;
+
+        let size = self
+            .window_settings
+            .apply(&self.window)
+            .unwrap_or_else(|| self.window.inner_size());
+
+        self.configure_surface(size);
+    }
+
+    pub fn render_stats(&self) -> RenderStats {
+        self.sprite_renderer.stats()
+    }
+
+    pub fn render_events(&mut self) -> &mut Events<RenderEvent> {
+        &mut self.render_events
+    }
+
+    fn configure_surface(&mut self, size: winit::dpi::PhysicalSize<u32>) {
+        if size.width == 0 || size.height == 0 {
+            if !self.minimized {
+                self.minimized = true;
+                self.render_events.send(RenderEvent::Minimized);
+            }
+
+            return;
+        }
+
+        if self.minimized {
+            self.minimized = false;
+            self.last_update = Instant::now();
+            self.render_events.send(RenderEvent::Restored);
         }
+
+        self.surface.configure(
+            &self.device,
+            &graphics::create_surface_config(size, &self.surface_support, &self.window_settings),
+        );
+    }
+
+    pub fn record_input(&mut self, path: &str) -> std::io::Result<()> {
+        self.simulation.record_input(path)
+    }
+
+    pub fn replay_input(&mut self, path: &str) -> std::io::Result<()> {
+        self.simulation.replay_input(path)
+    }
+
+    pub fn inject_input(&mut self, event: InputEvent) {
+        self.simulation.inject_input(event);
+    }
+
+    pub fn input(&self) -> &Input {
+        self.simulation.input()
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.simulation.tick()
+    }
+
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
     }
 
     pub fn process_window_event(
@@ -93,30 +198,189 @@ impl Game {
         event_loop: &winit::event_loop::ActiveEventLoop,
         event: winit::event::WindowEvent,
     ) {
-        asset_system::load_pending_assets(
-            &self.device,
-            &self.queue,
-            &mut self.asset_server,
-            &mut self.assets,
-            &mut self.resources,
-        );
+        if !self.simulation.is_replaying() {
+            if let Some(input_event) = InputEvent::from_window_event(&event) {
+                self.inject_input(input_event);
+            }
+        }
 
         match event {
             WindowEvent::RedrawRequested => {
+                self.update();
                 self.render();
                 self.window.request_redraw();
             }
             WindowEvent::Resized(size) => {
-                self.surface
-                    .configure(&self.device, &graphics::create_surface_config(size));
+                self.configure_surface(size);
+                render_system::resize_cameras(self.simulation.world(), size);
+            }
+            WindowEvent::CloseRequested => {
+                if let Err(err) = self.simulation.flush_recording() {
+                    log::error!("Failed to flush input recording: {err}");
+                }
+
+                event_loop.exit();
             }
-            WindowEvent::CloseRequested => event_loop.exit(),
             _ => {}
         }
     }
 
+    pub fn update(&mut self) {
+        asset_system::upload_loaded_assets(
+            &self.device,
+            &self.queue,
+            &mut self.asset_server,
+            &mut self.assets,
+            &mut self.resources,
+        );
+
+        asset_system::unload_unused_assets(
+            &mut self.asset_server,
+            &mut self.assets,
+            &mut self.resources,
+        );
+
+        let asset_events: Vec<_> = self.asset_server.events().drain().collect();
+        for event in asset_events {
+            match event {
+                AssetEvent::Modified(handle_id) => log::info!(
+                    "Reloaded \"{}\"",
+                    self.asset_server.get_path(handle_id).unwrap_or_default()
+                ),
+                AssetEvent::GroupFinished(name) => {
+                    if let Some(progress) = self.asset_server.group_progress(&name) {
+                        log::info!(
+                            "Loaded asset group \"{name}\" ({}/{} assets, {} failed, {} bytes)",
+                            progress.loaded,
+                            progress.total,
+                            progress.failed,
+                            progress.loaded_bytes
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let now = Instant::now();
+        self.accumulator += now - self.last_update;
+        self.last_update = now;
+
+        let mut ticks = 0;
+        while self.accumulator >= FIXED_TIMESTEP && ticks < MAX_TICKS_PER_UPDATE {
+            self.fixed_update();
+
+            self.accumulator -= FIXED_TIMESTEP;
+            ticks += 1;
+        }
+
+        if ticks == MAX_TICKS_PER_UPDATE {
+            self.accumulator = Duration::ZERO;
+        }
+    }
+
+    pub fn fixed_update(&mut self) {
+        self.simulation.fixed_update();
+    }
+
+    fn lose_device(&mut self, reason: String) {
+        log::warn!("Graphics device lost: {reason}");
+
+        self.device_retry_at = Some(Instant::now());
+        self.device_retry_delay = MIN_DEVICE_RETRY_DELAY;
+        self.render_events.send(RenderEvent::DeviceLost(reason));
+    }
+
+    fn recreate_device(&mut self) {
+        let result = graphics::request_device(&self.adapter).or_else(|_| {
+            let adapter = graphics::request_adapter(&self.instance, &self.surface)?;
+            let surface_support = graphics::negotiate_surface_support(&self.surface, &adapter)?;
+            let device = graphics::request_device(&adapter)?;
+
+            self.adapter = adapter;
+            self.surface_support = surface_support;
+
+            Ok::<_, graphics::GraphicsError>(device)
+        });
+
+        let (device, queue) = match result {
+            Ok(device) => device,
+            Err(err) => {
+                log::error!(
+                    "Failed to recreate the device, retrying in {:?}: {err}",
+                    self.device_retry_delay
+                );
+
+                self.device_retry_at = Some(Instant::now() + self.device_retry_delay);
+                self.device_retry_delay = (self.device_retry_delay * 2).min(MAX_DEVICE_RETRY_DELAY);
+                return;
+            }
+        };
+
+        self.device_retry_at = None;
+
+        self.device = Arc::new(device);
+        self.queue = Arc::new(queue);
+        self.device_lost = graphics::watch_device_lost(&self.device);
+
+        self.configure_surface(self.window.inner_size());
+
+        self.sprite_renderer = SpriteRenderer::new(
+            self.device.clone(),
+            self.queue.clone(),
+            self.surface_support.format,
+        );
+        self.resources = Resources::new();
+        asset_system::upload_assets(
+            &self.device,
+            &self.queue,
+            &mut self.asset_server,
+            self.assets.get::<Image>(),
+            &mut self.resources,
+        );
+
+        self.render_events.send(RenderEvent::DeviceRecreated);
+    }
+
     pub fn render(&mut self) {
-        let frame = self.surface.get_current_texture().unwrap();
+        let device_lost = self
+            .device_lost
+            .lock()
+            .ok()
+            .and_then(|mut device_lost| device_lost.take());
+
+        if let Some(reason) = device_lost {
+            self.lose_device(reason);
+        }
+
+        if let Some(retry_at) = self.device_retry_at {
+            if Instant::now() >= retry_at {
+                self.recreate_device();
+            }
+
+            return;
+        }
+
+        if self.minimized {
+            return;
+        }
+
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::SurfaceError::Timeout) => {
+                self.render_events.send(RenderEvent::SurfaceTimeout);
+                return;
+            }
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                self.configure_surface(self.window.inner_size());
+                self.render_events.send(RenderEvent::SurfaceReconfigured);
+                return;
+            }
+            Err(wgpu::SurfaceError::OutOfMemory) => {
+                self.lose_device(wgpu::SurfaceError::OutOfMemory.to_string());
+                return;
+            }
+        };
 
         let view = frame
             .texture
@@ -126,13 +390,27 @@ impl Game {
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
 
-        render_system::set_camera_projection(&self.world, &mut self.sprite_renderer);
-        render_system::draw_sprites(&self.world, &self.assets, &mut self.sprite_renderer);
+        render_system::set_camera_projection(self.simulation.world(), &mut self.sprite_renderer);
+        render_system::draw_sprites(
+            self.simulation.world(),
+            &self.asset_server,
+            self.assets.get::<Image>(),
+            self.assets.get::<TextureAtlas>(),
+            &self.resources.atlas,
+            self.sprite_renderer.batch_mut(),
+        );
 
         self.sprite_renderer
-            .render(&self.resources, &view, &mut encoder);
+            .render(&mut self.resources, &view, &mut encoder);
 
         self.queue.submit(std::iter::once(encoder.finish()));
+
+        let suboptimal = frame.suboptimal;
         frame.present();
+
+        if suboptimal {
+            self.configure_surface(self.window.inner_size());
+            self.render_events.send(RenderEvent::SurfaceReconfigured);
+        }
     }
 }
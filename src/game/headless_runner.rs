@@ -0,0 +1,116 @@
+use crate::core::{
+    assets::{
+        loaders::{ImageLoader, SpriteSheetLoader},
+        AssetServer,
+    },
+    window::WindowSettings,
+};
+
+use super::{scene, Simulation};
+
+// Steps the simulation without a window, surface or device so a replay always lands on the same
+// ticks, independent of frame timing.
+pub struct HeadlessRunner {
+    simulation: Simulation,
+    _asset_server: AssetServer,
+}
+
+impl HeadlessRunner {
+    pub fn new() -> Self {
+        let mut asset_server = AssetServer::new();
+        asset_server.register_loader(ImageLoader);
+        asset_server.register_loader(SpriteSheetLoader);
+        let world = scene::create_world(&mut asset_server, WindowSettings::default().size.into());
+
+        Self {
+            simulation: Simulation::new(world),
+            _asset_server: asset_server,
+        }
+    }
+
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
+    }
+
+    pub fn simulation_mut(&mut self) -> &mut Simulation {
+        &mut self.simulation
+    }
+
+    pub fn run(&mut self, ticks: u64) -> u64 {
+        for _ in 0..ticks {
+            self.simulation.fixed_update();
+        }
+
+        self.simulation.state_hash()
+    }
+}
+
+impl Default for HeadlessRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::{event::MouseButton, keyboard::KeyCode};
+
+    use crate::core::input::InputEvent;
+
+    use super::*;
+
+    const TICKS: u64 = 120;
+
+    fn script(tick: u64) -> Vec<InputEvent> {
+        match tick {
+            3 => vec![InputEvent::KeyPressed(KeyCode::ArrowRight)],
+            10 => vec![
+                InputEvent::CursorMoved { x: 40.0, y: 25.5 },
+                InputEvent::MouseButtonPressed(MouseButton::Left),
+            ],
+            42 => vec![InputEvent::KeyReleased(KeyCode::ArrowRight)],
+            90 => vec![
+                InputEvent::MouseWheel { x: 0.0, y: -1.0 },
+                InputEvent::KeyPressed(KeyCode::Space),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn replay_reaches_the_recorded_state_hash() {
+        let path = std::env::temp_dir().join(format!("corvus-replay-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut recording = HeadlessRunner::new();
+        recording.simulation_mut().record_input(path).unwrap();
+        for tick in 0..TICKS {
+            for event in script(tick) {
+                recording.simulation_mut().inject_input(event);
+            }
+            recording.run(1);
+        }
+        recording.simulation_mut().flush_recording().unwrap();
+        let recorded_hash = recording.simulation().state_hash();
+
+        let mut replay = HeadlessRunner::new();
+        replay.simulation_mut().replay_input(path).unwrap();
+        let replayed_hash = replay.run(TICKS);
+
+        let idle_hash = HeadlessRunner::new().run(TICKS);
+
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(recorded_hash, replayed_hash);
+        assert_ne!(recorded_hash, idle_hash);
+    }
+
+    #[test]
+    fn state_hash_depends_on_the_tick() {
+        let mut runner = HeadlessRunner::new();
+        let first = runner.simulation().state_hash();
+
+        assert_ne!(first, runner.run(1));
+        assert_eq!(runner.simulation().tick(), 1);
+    }
+}
@@ -1,3 +1,9 @@
+#[allow(clippy::module_inception)]
 mod game;
+mod headless_runner;
+mod scene;
+mod simulation;
 
 pub use game::Game;
+pub use headless_runner::HeadlessRunner;
+pub use simulation::Simulation;
@@ -0,0 +1,49 @@
+use crate::core::{
+    assets::{AssetServer, TextureAtlas},
+    ecs::{
+        components::{AtlasSprite, OrthoCamera, ScalingMode, Sprite, Transform},
+        World,
+    },
+};
+
+pub fn create_world(
+    asset_server: &mut AssetServer,
+    viewport: winit::dpi::PhysicalSize<u32>,
+) -> World {
+    let mut world = World::new();
+    world.register_component::<Transform>();
+    world.register_component::<Sprite>();
+    world.register_component::<AtlasSprite>();
+    world.register_component::<OrthoCamera>();
+
+    let c = world.spawn();
+    world.insert_component(
+        c,
+        OrthoCamera::new(glam::vec2(0.0, 0.0), viewport, 1.0).with_scaling_mode(
+            ScalingMode::PixelPerfect {
+                width: 320,
+                height: 180,
+            },
+        ),
+    );
+
+    let e = world.spawn();
+    world.insert_component(
+        e,
+        Transform::new(
+            glam::vec3(16.0, 0.0, 0.0),
+            glam::vec2(1.0, 1.0),
+            0.0,
+            glam::vec2(0.0, 1.0),
+        ),
+    );
+
+    let idle_atlas = asset_server.load::<TextureAtlas>("character/idle.png#atlas");
+
+    world.insert_component(
+        e,
+        AtlasSprite::new(idle_atlas, 0, [1.0, 1.0, 1.0, 1.0], false, false),
+    );
+
+    world
+}
@@ -0,0 +1,113 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::core::{
+    ecs::{components::Transform, World},
+    input::{Input, InputEvent, InputPlayback, InputRecorder},
+};
+
+pub struct Simulation {
+    world: World,
+
+    input: Input,
+    pending_input: Vec<InputEvent>,
+    input_recorder: Option<InputRecorder>,
+    input_playback: Option<InputPlayback>,
+
+    tick: u64,
+}
+
+impl Simulation {
+    pub fn new(world: World) -> Self {
+        Self {
+            world,
+
+            input: Input::new(),
+            pending_input: Vec::new(),
+            input_recorder: None,
+            input_playback: None,
+
+            tick: 0,
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn record_input(&mut self, path: &str) -> std::io::Result<()> {
+        self.input_recorder = Some(InputRecorder::create(path)?);
+
+        Ok(())
+    }
+
+    pub fn replay_input(&mut self, path: &str) -> std::io::Result<()> {
+        self.input_playback = Some(InputPlayback::open(path)?);
+
+        Ok(())
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.input_playback.is_some()
+    }
+
+    pub fn flush_recording(&mut self) -> std::io::Result<()> {
+        match &mut self.input_recorder {
+            Some(recorder) => recorder.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn inject_input(&mut self, event: InputEvent) {
+        self.pending_input.push(event);
+    }
+
+    pub fn fixed_update(&mut self) {
+        self.input.clear_frame();
+
+        let events = match &mut self.input_playback {
+            Some(playback) => playback.events_for_tick(self.tick),
+            None => std::mem::take(&mut self.pending_input),
+        };
+
+        for event in &events {
+            self.input.apply(event);
+        }
+
+        if let Some(recorder) = &mut self.input_recorder {
+            if let Err(err) = recorder.record(self.tick, &events) {
+                log::error!("Failed to record input for tick {}: {err}", self.tick);
+            }
+        }
+
+        self.tick += 1;
+    }
+
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.tick.hash(&mut hasher);
+        self.input.hash(&mut hasher);
+
+        for entity in self.world.entities() {
+            entity.hash(&mut hasher);
+            self.world
+                .get_component::<Transform>(entity)
+                .as_deref()
+                .hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+}
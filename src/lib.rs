@@ -2,4 +2,4 @@ pub mod core;
 
 mod game;
 
-pub use game::Game;
+pub use game::{Game, HeadlessRunner, Simulation};
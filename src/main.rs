@@ -1,28 +1,114 @@
-use winit::{
-    application::ApplicationHandler, dpi::PhysicalSize, event_loop::EventLoop, window::Window,
-};
+use winit::{application::ApplicationHandler, event_loop::EventLoop};
 
-use corvus::Game;
+use corvus::{core::window::WindowSettings, Game, HeadlessRunner};
+
+const WINDOW_SETTINGS_PATH: &str = "window.json";
+
+enum InputMode {
+    Live,
+    Record(String),
+    Replay(String),
+}
+
+impl InputMode {
+    fn from_args() -> Self {
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--record-input" => return args.next().map_or(Self::Live, Self::Record),
+                "--replay-input" => return args.next().map_or(Self::Live, Self::Replay),
+                _ => {}
+            }
+        }
+
+        Self::Live
+    }
+}
+
+fn headless_ticks_from_args() -> Option<u64> {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--headless" {
+            return args.next().and_then(|ticks| ticks.parse().ok());
+        }
+    }
+
+    None
+}
+
+fn run_headless(ticks: u64, input_mode: &InputMode) {
+    let mut runner = HeadlessRunner::new();
+
+    let result = match input_mode {
+        InputMode::Live => Ok(()),
+        InputMode::Record(path) => runner.simulation_mut().record_input(path),
+        InputMode::Replay(path) => runner.simulation_mut().replay_input(path),
+    };
+
+    if let Err(err) = result {
+        log::error!("Failed to set up input recording: {err}");
+        return;
+    }
+
+    let hash = runner.run(ticks);
+
+    if let Err(err) = runner.simulation_mut().flush_recording() {
+        log::error!("Failed to flush input recording: {err}");
+    }
+
+    println!("tick {ticks}: world state hash {hash:016x}");
+}
 
 struct WinitApp {
     game: Option<Game>,
+    input_mode: InputMode,
 }
 
 impl WinitApp {
-    pub fn new() -> Self {
-        Self { game: None }
+    pub fn new(input_mode: InputMode) -> Self {
+        Self {
+            game: None,
+            input_mode,
+        }
     }
 }
 
 impl ApplicationHandler for WinitApp {
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        let window_attributes = Window::default_attributes()
-            .with_title("Corvus")
-            .with_inner_size(PhysicalSize::new(1280, 720));
+        let window_settings = match WindowSettings::load(WINDOW_SETTINGS_PATH) {
+            Ok(window_settings) => window_settings,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => WindowSettings::default(),
+            Err(err) => {
+                log::warn!("Failed to load \"{WINDOW_SETTINGS_PATH}\", using defaults: {err}");
+                WindowSettings::default()
+            }
+        };
 
+        let window_attributes = window_settings.window_attributes(event_loop.primary_monitor());
         let window = event_loop.create_window(window_attributes).unwrap();
 
-        self.game = Some(Game::new(window));
+        let mut game = match Game::new(window, window_settings) {
+            Ok(game) => game,
+            Err(err) => {
+                log::error!("Failed to initialize graphics: {err}");
+                event_loop.exit();
+                return;
+            }
+        };
+
+        let result = match &self.input_mode {
+            InputMode::Live => Ok(()),
+            InputMode::Record(path) => game.record_input(path),
+            InputMode::Replay(path) => game.replay_input(path),
+        };
+
+        if let Err(err) = result {
+            log::error!("Failed to set up input recording: {err}");
+        }
+
+        self.game = Some(game);
     }
 
     fn window_event(
@@ -37,11 +123,24 @@ impl ApplicationHandler for WinitApp {
 
         if let Some(game) = &mut self.game {
             game.process_window_event(event_loop, event);
+
+            for event in game.render_events().drain() {
+                log::debug!("Render event: {event:?}");
+            }
         };
     }
 }
 
 fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let input_mode = InputMode::from_args();
+
+    if let Some(ticks) = headless_ticks_from_args() {
+        run_headless(ticks, &input_mode);
+        return;
+    }
+
     let event_loop = EventLoop::new().unwrap();
-    _ = event_loop.run_app(&mut WinitApp::new());
+    _ = event_loop.run_app(&mut WinitApp::new(input_mode));
 }